@@ -0,0 +1,238 @@
+//! Base32 encoding and decoding of `Bytes<N>` (RFC 4648), with the standard
+//! and the extended hex alphabet, padded or not.
+
+use core::fmt;
+
+use heapless::{String, Vec};
+
+use crate::{Bytes, CapacityError};
+
+const STANDARD: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const HEX: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+const PAD: u8 = b'=';
+
+/// Base32 alphabet and padding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// Standard alphabet, `A` to `Z` and `2` to `7`, padded with `=`.
+    Standard,
+    /// Standard alphabet, without padding, as used for TOTP secrets.
+    StandardNoPad,
+    /// Extended hex alphabet, `0` to `9` and `A` to `V`, padded with `=`.
+    Hex,
+    /// Extended hex alphabet, without padding.
+    HexNoPad,
+}
+
+impl Variant {
+    const fn alphabet(self) -> &'static [u8; 32] {
+        match self {
+            Variant::Standard | Variant::StandardNoPad => STANDARD,
+            Variant::Hex | Variant::HexNoPad => HEX,
+        }
+    }
+
+    const fn padded(self) -> bool {
+        matches!(self, Variant::Standard | Variant::Hex)
+    }
+}
+
+/// Length of the base32 of `len` bytes.
+///
+/// The padded variants give an upper bound for all of them, also available
+/// as [`Bytes::BASE32_LEN`] for a full `Bytes<N>`.
+pub const fn encoded_len(len: usize, variant: Variant) -> usize {
+    let groups = len / 5 * 8;
+    match (len % 5, variant.padded()) {
+        (0, _) => groups,
+        (_, true) => groups + 8,
+        (rest, false) => groups + (rest * 8).div_ceil(5),
+    }
+}
+
+/// Encodes one to five bytes into two to eight symbols, plus padding if the
+/// variant has it. Returns the symbols and how many of them to use.
+fn encode_group(group: &[u8], variant: Variant) -> ([u8; 8], usize) {
+    let mut bits = 0u64;
+    for (i, byte) in group.iter().enumerate() {
+        bits |= u64::from(*byte) << (32 - 8 * i);
+    }
+    let alphabet = variant.alphabet();
+    let mut symbols = [PAD; 8];
+    let len = (group.len() * 8).div_ceil(5);
+    for (i, symbol) in symbols.iter_mut().enumerate().take(len) {
+        *symbol = alphabet[(bits >> (35 - 5 * i)) as usize & 0x1f];
+    }
+    let count = if variant.padded() { 8 } else { len };
+    (symbols, count)
+}
+
+/// Displays bytes in base32, returned by [`Bytes::base32`].
+pub struct Base32<'a> {
+    bytes: &'a [u8],
+    variant: Variant,
+}
+
+impl fmt::Display for Base32<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = [0u8; 64];
+        for chunk in self.bytes.chunks(buffer.len() / 8 * 5) {
+            let mut len = 0;
+            for group in chunk.chunks(5) {
+                let (symbols, count) = encode_group(group, self.variant);
+                buffer[len..][..count].copy_from_slice(&symbols[..count]);
+                len += count;
+            }
+            f.write_str(core::str::from_utf8(&buffer[..len]).map_err(|_| fmt::Error)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when decoding base32 into `Bytes<N>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Base32Error {
+    /// No encoding has this many symbols.
+    InvalidLength,
+    /// The character at byte offset `index` is not in the alphabet.
+    InvalidSymbol { index: usize },
+    /// Padding is misplaced, or the variant has none.
+    InvalidPadding,
+    /// The last symbol has bits set past the end of the data, so this is not
+    /// the canonical encoding.
+    NonCanonical,
+    /// The decoded bytes do not fit.
+    Capacity(CapacityError),
+}
+
+impl From<CapacityError> for Base32Error {
+    fn from(error: CapacityError) -> Self {
+        Base32Error::Capacity(error)
+    }
+}
+
+impl fmt::Display for Base32Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Base32Error::InvalidLength => f.write_str("invalid base32 length"),
+            Base32Error::InvalidSymbol { index } => {
+                write!(f, "invalid base32 symbol at index {}", index)
+            }
+            Base32Error::InvalidPadding => f.write_str("invalid base32 padding"),
+            Base32Error::NonCanonical => f.write_str("non-canonical base32"),
+            Base32Error::Capacity(error) => error.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for Base32Error {}
+
+fn symbol(c: u8, index: usize, variant: Variant) -> Result<u64, Base32Error> {
+    match variant.alphabet().iter().position(|&s| s == c) {
+        Some(value) => Ok(value as u64),
+        None if c == PAD => Err(Base32Error::InvalidPadding),
+        None => Err(Base32Error::InvalidSymbol { index }),
+    }
+}
+
+/// Checks the length of `base32` and strips its padding. Returns how many
+/// symbols are left, and the length of the decoded bytes.
+fn unpad(base32: &[u8], variant: Variant) -> Result<(usize, usize), Base32Error> {
+    let mut len = base32.len();
+    if variant.padded() {
+        if len % 8 != 0 {
+            return Err(Base32Error::InvalidLength);
+        }
+        // any padding left in the symbols is misplaced
+        len -= base32
+            .iter()
+            .rev()
+            .take(6)
+            .take_while(|&&c| c == PAD)
+            .count();
+    }
+    // a partial group has 2, 4, 5 or 7 symbols, for 1 to 4 bytes
+    if matches!(len % 8, 1 | 3 | 6) {
+        return Err(Base32Error::InvalidLength);
+    }
+    Ok((len, len / 8 * 5 + len % 8 * 5 / 8))
+}
+
+/// Decodes two to eight symbols, starting at byte offset `index`, into one
+/// to five bytes. Returns the bytes and how many of them to use.
+fn decode_group(
+    group: &[u8],
+    index: usize,
+    variant: Variant,
+) -> Result<([u8; 5], usize), Base32Error> {
+    let mut bits = 0u64;
+    for (i, &c) in group.iter().enumerate() {
+        bits = bits << 5 | symbol(c, index + i, variant)?;
+    }
+    bits <<= 5 * (8 - group.len());
+    let count = group.len() * 5 / 8;
+    if bits & (0xff_ffff_ffff >> (8 * count)) != 0 {
+        return Err(Base32Error::NonCanonical);
+    }
+    let mut bytes = [0; 5];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (bits >> (32 - 8 * i)) as u8;
+    }
+    Ok((bytes, count))
+}
+
+impl<const N: usize> Bytes<N> {
+    /// Length of the padded base32 of `N` bytes, enough for any [`Variant`].
+    ///
+    /// Use it to size the `heapless::String` for [`to_base32`](Self::to_base32).
+    pub const BASE32_LEN: usize = encoded_len(N, Variant::Standard);
+
+    /// Decode base32, rejecting anything but the canonical encoding in
+    /// `variant`. Lowercase letters are not accepted.
+    pub fn from_base32(base32: &str, variant: Variant) -> Result<Self, Base32Error> {
+        let base32 = base32.as_bytes();
+        let (symbols, len) = unpad(base32, variant)?;
+        if len > N {
+            return Err(Self::capacity_error(len).into());
+        }
+
+        let mut bytes = Vec::new();
+        for (i, group) in base32[..symbols].chunks(8).enumerate() {
+            let (decoded, count) = decode_group(group, 8 * i, variant)?;
+            for &byte in decoded.iter().take(count) {
+                bytes.push(byte).ok();
+            }
+        }
+        Ok(Self::from(bytes))
+    }
+
+    /// Encode the contents as base32.
+    ///
+    /// `M` must be at least [`BASE32_LEN`](Self::BASE32_LEN), which is
+    /// checked at compile time.
+    pub fn to_base32<const M: usize>(&self, variant: Variant) -> String<M> {
+        const {
+            assert!(
+                M >= encoded_len(N, Variant::Standard),
+                "String<M> is too short for the base32 of Bytes<N>"
+            )
+        };
+        let mut base32 = String::new();
+        for group in self.chunks(5) {
+            let (symbols, count) = encode_group(group, variant);
+            for &symbol in symbols.iter().take(count) {
+                base32.push(char::from(symbol)).ok();
+            }
+        }
+        base32
+    }
+
+    /// Display the contents as base32, without allocating.
+    pub fn base32(&self, variant: Variant) -> Base32<'_> {
+        Base32 {
+            bytes: self,
+            variant,
+        }
+    }
+}
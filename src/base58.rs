@@ -0,0 +1,215 @@
+//! Base58 encoding and decoding of `Bytes<N>`, with the Bitcoin alphabet,
+//! and with the `base58check` feature, Base58Check.
+//!
+//! Base58 treats the bytes as one big number, so encoding and decoding take
+//! time quadratic in the length. Each leading zero byte is encoded as a `1`.
+
+use core::fmt;
+
+use heapless::{String, Vec};
+
+use crate::{Bytes, CapacityError};
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Upper bound for the length of the base58 of `len` bytes.
+///
+/// Also available as [`Bytes::BASE58_LEN`] for a full `Bytes<N>`.
+pub const fn max_encoded_len(len: usize) -> usize {
+    // log(256) / log(58) is less than 1.38
+    len * 138 / 100 + 1
+}
+
+/// Error returned when decoding base58 into `Bytes<N>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Base58Error {
+    /// The character at byte offset `index` is not in the alphabet.
+    InvalidSymbol { index: usize },
+    /// The decoded bytes are too short to hold a checksum.
+    InvalidLength,
+    /// The checksum does not match the decoded bytes.
+    Checksum,
+    /// The decoded bytes do not fit.
+    ///
+    /// If they are longer than the capacity, `requested` is only a lower
+    /// bound, one more than the capacity.
+    Capacity(CapacityError),
+}
+
+impl From<CapacityError> for Base58Error {
+    fn from(error: CapacityError) -> Self {
+        Base58Error::Capacity(error)
+    }
+}
+
+impl fmt::Display for Base58Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Base58Error::InvalidSymbol { index } => {
+                write!(f, "invalid base58 symbol at index {}", index)
+            }
+            Base58Error::InvalidLength => f.write_str("base58 too short for a checksum"),
+            Base58Error::Checksum => f.write_str("base58 checksum mismatch"),
+            Base58Error::Capacity(error) => error.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for Base58Error {}
+
+/// Encodes `bytes` as base58.
+fn encode<const M: usize>(bytes: impl Iterator<Item = u8> + Clone) -> String<M> {
+    let zeros = bytes.clone().take_while(|&byte| byte == 0).count();
+    // the number in base 58, little-endian
+    let mut digits = Vec::<u8, M>::new();
+    for byte in bytes {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8).ok();
+            carry /= 58;
+        }
+    }
+
+    let mut base58 = String::new();
+    for _ in 0..zeros {
+        base58.push('1').ok();
+    }
+    for &digit in digits.iter().rev() {
+        base58.push(char::from(ALPHABET[usize::from(digit)])).ok();
+    }
+    base58
+}
+
+/// Decodes `base58` as a number, little-endian, into the fixed `low` bytes
+/// and then `high`, which grows as needed. Returns the number of leading
+/// zero bytes, encoded as `1`s.
+fn decode<const N: usize>(
+    base58: &[u8],
+    low: &mut [u8],
+    high: &mut Vec<u8, N>,
+) -> Result<usize, Base58Error> {
+    for (index, &c) in base58.iter().enumerate() {
+        let mut carry = ALPHABET
+            .iter()
+            .position(|&s| s == c)
+            .ok_or(Base58Error::InvalidSymbol { index })? as u32;
+        for byte in low.iter_mut().chain(high.iter_mut()) {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            high.push(carry as u8)
+                .map_err(|_| Bytes::<N>::capacity_error(N.saturating_add(1)))?;
+            carry >>= 8;
+        }
+    }
+    Ok(base58.iter().take_while(|&&c| c == b'1').count())
+}
+
+/// Builds `Bytes<N>` from `zeros` zero bytes followed by `high`, reversed.
+fn collect<const N: usize>(zeros: usize, high: &[u8]) -> Result<Bytes<N>, Base58Error> {
+    let len = zeros.saturating_add(high.len());
+    if len > N {
+        return Err(Bytes::<N>::capacity_error(len).into());
+    }
+    let mut bytes = Vec::new();
+    for _ in 0..zeros {
+        bytes.push(0).ok();
+    }
+    for &byte in high.iter().rev() {
+        bytes.push(byte).ok();
+    }
+    Ok(Bytes::from(bytes))
+}
+
+#[cfg(feature = "base58check")]
+fn checksum(bytes: &[u8]) -> [u8; 4] {
+    use sha2::{Digest, Sha256};
+
+    let hash = Sha256::digest(Sha256::digest(bytes));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+impl<const N: usize> Bytes<N> {
+    /// Upper bound for the length of the base58 of `N` bytes.
+    ///
+    /// Use it to size the `heapless::String` for [`to_base58`](Self::to_base58).
+    pub const BASE58_LEN: usize = max_encoded_len(N);
+
+    /// Upper bound for the length of the Base58Check of `N` bytes, which
+    /// includes the four bytes of the checksum.
+    #[cfg(feature = "base58check")]
+    pub const BASE58CHECK_LEN: usize = max_encoded_len(N + 4);
+
+    /// Decode base58.
+    pub fn from_base58(base58: &str) -> Result<Self, Base58Error> {
+        let mut high = Vec::<u8, N>::new();
+        let zeros = decode(base58.as_bytes(), &mut [], &mut high)?;
+        collect(zeros, &high)
+    }
+
+    /// Encode the contents as base58.
+    ///
+    /// `M` must be at least [`BASE58_LEN`](Self::BASE58_LEN), which is
+    /// checked at compile time.
+    pub fn to_base58<const M: usize>(&self) -> String<M> {
+        const {
+            assert!(
+                M >= max_encoded_len(N),
+                "String<M> is too short for the base58 of Bytes<N>"
+            )
+        };
+        encode(self.iter().copied())
+    }
+
+    /// Decode Base58Check, and verify and strip its checksum: the first
+    /// four bytes of the double SHA-256 of the rest.
+    ///
+    /// Any version byte stays at the start of the returned bytes.
+    #[cfg(feature = "base58check")]
+    pub fn from_base58check(base58: &str) -> Result<Self, Base58Error> {
+        let mut low = [0; 4];
+        let mut high = Vec::<u8, N>::new();
+        let zeros = decode(base58.as_bytes(), &mut low, &mut high)?;
+        // without `high`, the zero bytes at the top of `low` are leading
+        // zeros, and already counted
+        let overlap = if high.is_empty() {
+            low.iter().rev().take_while(|&&byte| byte == 0).count()
+        } else {
+            0
+        };
+        let zeros = zeros
+            .checked_sub(overlap)
+            .ok_or(Base58Error::InvalidLength)?;
+
+        let bytes = collect(zeros, &high)?;
+        low.reverse();
+        if checksum(&bytes) != low {
+            return Err(Base58Error::Checksum);
+        }
+        Ok(bytes)
+    }
+
+    /// Encode the contents followed by their checksum as Base58Check.
+    ///
+    /// `M` must be at least [`BASE58CHECK_LEN`](Self::BASE58CHECK_LEN),
+    /// which is checked at compile time.
+    #[cfg(feature = "base58check")]
+    pub fn to_base58check<const M: usize>(&self) -> String<M> {
+        const {
+            assert!(
+                M >= max_encoded_len(N + 4),
+                "String<M> is too short for the Base58Check of Bytes<N>"
+            )
+        };
+        let checksum = checksum(self);
+        encode(self.iter().chain(checksum.iter()).copied())
+    }
+}
@@ -0,0 +1,345 @@
+//! Base64 encoding and decoding of `Bytes<N>` (RFC 4648), with the standard
+//! and the URL-safe alphabet, padded or not.
+
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use heapless::{String, Vec};
+use serde::{
+    de::{Deserialize, Deserializer, Unexpected, Visitor},
+    ser::{Serialize, Serializer},
+};
+
+use crate::{Bytes, CapacityError};
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const PAD: u8 = b'=';
+
+/// Base64 alphabet and padding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// Standard alphabet, with `+` and `/`, padded with `=`.
+    Standard,
+    /// Standard alphabet, without padding.
+    StandardNoPad,
+    /// URL-safe alphabet, with `-` and `_`, padded with `=`.
+    UrlSafe,
+    /// URL-safe alphabet, without padding, as used by JWTs and WebAuthn.
+    UrlSafeNoPad,
+}
+
+impl Variant {
+    const fn alphabet(self) -> &'static [u8; 64] {
+        match self {
+            Variant::Standard | Variant::StandardNoPad => STANDARD,
+            Variant::UrlSafe | Variant::UrlSafeNoPad => URL_SAFE,
+        }
+    }
+
+    const fn padded(self) -> bool {
+        matches!(self, Variant::Standard | Variant::UrlSafe)
+    }
+}
+
+/// Length of the base64 of `len` bytes.
+///
+/// The padded variants give an upper bound for all of them, also available
+/// as [`Bytes::BASE64_LEN`] for a full `Bytes<N>`.
+pub const fn encoded_len(len: usize, variant: Variant) -> usize {
+    let groups = len / 3 * 4;
+    match (len % 3, variant.padded()) {
+        (0, _) => groups,
+        (_, true) => groups + 4,
+        (rest, false) => groups + rest + 1,
+    }
+}
+
+/// Encodes one to three bytes into two to four symbols, plus padding if the
+/// variant has it. Returns the symbols and how many of them to use.
+fn encode_group(group: &[u8], variant: Variant) -> ([u8; 4], usize) {
+    let mut bits = 0u32;
+    for (i, byte) in group.iter().enumerate() {
+        bits |= u32::from(*byte) << (16 - 8 * i);
+    }
+    let alphabet = variant.alphabet();
+    let mut symbols = [PAD; 4];
+    for (i, symbol) in symbols.iter_mut().enumerate().take(group.len() + 1) {
+        *symbol = alphabet[(bits >> (18 - 6 * i)) as usize & 0x3f];
+    }
+    let count = if variant.padded() { 4 } else { group.len() + 1 };
+    (symbols, count)
+}
+
+/// Displays bytes in base64, returned by [`Bytes::base64`].
+pub struct Base64<'a> {
+    bytes: &'a [u8],
+    variant: Variant,
+}
+
+impl fmt::Display for Base64<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = [0u8; 64];
+        for chunk in self.bytes.chunks(buffer.len() / 4 * 3) {
+            let mut len = 0;
+            for group in chunk.chunks(3) {
+                let (symbols, count) = encode_group(group, self.variant);
+                buffer[len..][..count].copy_from_slice(&symbols[..count]);
+                len += count;
+            }
+            f.write_str(core::str::from_utf8(&buffer[..len]).map_err(|_| fmt::Error)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when decoding base64 into `Bytes<N>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Base64Error {
+    /// No encoding has this many symbols.
+    InvalidLength,
+    /// The character at byte offset `index` is not in the alphabet.
+    InvalidSymbol { index: usize },
+    /// Padding is misplaced, or the variant has none.
+    InvalidPadding,
+    /// The last symbol has bits set past the end of the data, so this is not
+    /// the canonical encoding.
+    NonCanonical,
+    /// The decoded bytes do not fit.
+    Capacity(CapacityError),
+}
+
+impl From<CapacityError> for Base64Error {
+    fn from(error: CapacityError) -> Self {
+        Base64Error::Capacity(error)
+    }
+}
+
+impl fmt::Display for Base64Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Base64Error::InvalidLength => f.write_str("invalid base64 length"),
+            Base64Error::InvalidSymbol { index } => {
+                write!(f, "invalid base64 symbol at index {}", index)
+            }
+            Base64Error::InvalidPadding => f.write_str("invalid base64 padding"),
+            Base64Error::NonCanonical => f.write_str("non-canonical base64"),
+            Base64Error::Capacity(error) => error.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for Base64Error {}
+
+fn symbol(c: u8, index: usize, variant: Variant) -> Result<u32, Base64Error> {
+    match variant.alphabet().iter().position(|&s| s == c) {
+        Some(value) => Ok(value as u32),
+        None if c == PAD => Err(Base64Error::InvalidPadding),
+        None => Err(Base64Error::InvalidSymbol { index }),
+    }
+}
+
+/// Checks the length of `base64` and strips its padding. Returns how many
+/// symbols are left, and the length of the decoded bytes.
+fn unpad(base64: &[u8], variant: Variant) -> Result<(usize, usize), Base64Error> {
+    let mut len = base64.len();
+    if variant.padded() {
+        if len % 4 != 0 {
+            return Err(Base64Error::InvalidLength);
+        }
+        // any padding left in the symbols is misplaced
+        len -= base64
+            .iter()
+            .rev()
+            .take(2)
+            .take_while(|&&c| c == PAD)
+            .count();
+    }
+    if len % 4 == 1 {
+        return Err(Base64Error::InvalidLength);
+    }
+    Ok((len, len / 4 * 3 + (len % 4).saturating_sub(1)))
+}
+
+/// Decodes two to four symbols, starting at byte offset `index`, into one
+/// to three bytes. Returns the bytes and how many of them to use.
+fn decode_group(
+    group: &[u8],
+    index: usize,
+    variant: Variant,
+) -> Result<([u8; 3], usize), Base64Error> {
+    let mut bits = 0u32;
+    for (i, &c) in group.iter().enumerate() {
+        bits = bits << 6 | symbol(c, index + i, variant)?;
+    }
+    bits <<= 6 * (4 - group.len());
+    let count = group.len() * 6 / 8;
+    if bits & (0xff_ffff >> (8 * count)) != 0 {
+        return Err(Base64Error::NonCanonical);
+    }
+    Ok(([(bits >> 16) as u8, (bits >> 8) as u8, bits as u8], count))
+}
+
+impl<const N: usize> Bytes<N> {
+    /// Length of the padded base64 of `N` bytes, enough for any [`Variant`].
+    ///
+    /// Use it to size the `heapless::String` for [`to_base64`](Self::to_base64).
+    pub const BASE64_LEN: usize = encoded_len(N, Variant::Standard);
+
+    /// Decode base64, rejecting anything but the canonical encoding in
+    /// `variant`.
+    pub fn from_base64(base64: &str, variant: Variant) -> Result<Self, Base64Error> {
+        let base64 = base64.as_bytes();
+        let (symbols, len) = unpad(base64, variant)?;
+        if len > N {
+            return Err(Self::capacity_error(len).into());
+        }
+
+        let mut bytes = Vec::new();
+        for (i, group) in base64[..symbols].chunks(4).enumerate() {
+            let (decoded, count) = decode_group(group, 4 * i, variant)?;
+            for &byte in decoded.iter().take(count) {
+                bytes.push(byte).ok();
+            }
+        }
+        Ok(Self::from(bytes))
+    }
+
+    /// Decode base64 in place, reusing the buffer of the string.
+    ///
+    /// Like [`from_base64`](Self::from_base64), but as the decoded bytes
+    /// are shorter than the base64, they always fit.
+    pub fn from_base64_string(base64: String<N>, variant: Variant) -> Result<Self, Base64Error> {
+        let mut bytes = Self::from(base64.into_bytes());
+        let (symbols, len) = unpad(&bytes, variant)?;
+
+        for i in 0..symbols.div_ceil(4) {
+            let group = bytes.get(4 * i..symbols.min(4 * i + 4)).unwrap_or_default();
+            let (decoded, count) = decode_group(group, 4 * i, variant)?;
+            // writing behind the group that was just read
+            for (j, &byte) in decoded.iter().take(count).enumerate() {
+                if let Some(slot) = bytes.get_mut(3 * i + j) {
+                    *slot = byte;
+                }
+            }
+        }
+        bytes.truncate(len);
+        Ok(bytes)
+    }
+
+    /// Encode the contents as base64.
+    ///
+    /// `M` must be at least [`BASE64_LEN`](Self::BASE64_LEN), which is
+    /// checked at compile time.
+    pub fn to_base64<const M: usize>(&self, variant: Variant) -> String<M> {
+        const {
+            assert!(
+                M >= encoded_len(N, Variant::Standard),
+                "String<M> is too short for the base64 of Bytes<N>"
+            )
+        };
+        let mut base64 = String::new();
+        for group in self.chunks(3) {
+            let (symbols, count) = encode_group(group, variant);
+            for &symbol in symbols.iter().take(count) {
+                base64.push(char::from(symbol)).ok();
+            }
+        }
+        base64
+    }
+
+    /// Display the contents as base64, without allocating.
+    pub fn base64(&self, variant: Variant) -> Base64<'_> {
+        Base64 {
+            bytes: self,
+            variant,
+        }
+    }
+}
+
+/// `Bytes<N>` that serializes as a base64 string in human-readable formats.
+///
+/// Binary formats still use compact bytes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Base64Bytes<const N: usize>(pub Bytes<N>);
+
+impl<const N: usize> From<Bytes<N>> for Base64Bytes<N> {
+    fn from(bytes: Bytes<N>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const N: usize> From<Base64Bytes<N>> for Bytes<N> {
+    fn from(bytes: Base64Bytes<N>) -> Self {
+        bytes.0
+    }
+}
+
+impl<const N: usize> Deref for Base64Bytes<N> {
+    type Target = Bytes<N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for Base64Bytes<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for Base64Bytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> Serialize for Base64Bytes<N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&self.0.base64(Variant::Standard))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Base64Bytes<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Base64Visitor<const N: usize>;
+
+        impl<const N: usize> Visitor<'_> for Base64Visitor<N> {
+            type Value = Base64Bytes<N>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(formatter, "a base64 string of at most {} bytes", N)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                match Bytes::from_base64(v, Variant::Standard) {
+                    Ok(bytes) => Ok(Base64Bytes(bytes)),
+                    Err(Base64Error::Capacity(error)) => {
+                        Err(E::invalid_length(error.requested, &self))
+                    }
+                    Err(_) => Err(E::invalid_value(Unexpected::Str(v), &self)),
+                }
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(Base64Visitor)
+        } else {
+            Bytes::deserialize(deserializer).map(Base64Bytes)
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! [`bincode`](https://docs.rs/bincode) 2 encoding of and into `Bytes<N>`.
+//!
+//! `Bytes<N>` has the same wire format as `Vec<u8>`: its length as a `u64`,
+//! followed by the bytes.
+
+use ::bincode::{
+    config,
+    de::{read::Reader, BorrowDecode, BorrowDecoder, Decode, Decoder},
+    enc::{
+        write::{SizeWriter, Writer},
+        Encode, Encoder, EncoderImpl,
+    },
+    error::{DecodeError, EncodeError},
+};
+
+use crate::{Bytes, CodecError};
+
+impl<const N: usize> Encode for Bytes<N> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        (self.len() as u64).encode(encoder)?;
+        encoder.writer().write(self)
+    }
+}
+
+impl<Context, const N: usize> Decode<Context> for Bytes<N> {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let len = u64::decode(decoder)?;
+        let len = usize::try_from(len).map_err(|_| DecodeError::OutsideUsizeRange(len))?;
+        decoder.claim_bytes_read(len)?;
+
+        let mut bytes = Self::new();
+        bytes
+            .resize_default(len)
+            .map_err(|_| DecodeError::Other("byte string exceeds the capacity of Bytes<N>"))?;
+        decoder.reader().read(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl<'de, Context, const N: usize> BorrowDecode<'de, Context> for Bytes<N> {
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        Self::decode(decoder)
+    }
+}
+
+impl<const N: usize> Bytes<N> {
+    /// Encode `t` with bincode's standard configuration into a new `Bytes<N>`.
+    pub fn try_from_bincode<T>(t: &T) -> Result<Self, CodecError>
+    where
+        T: Encode + ?Sized,
+    {
+        let mut bytes = Self::new();
+        bytes.resize_to_capacity();
+
+        match ::bincode::encode_into_slice(t, &mut bytes, config::standard()) {
+            Ok(size) => {
+                bytes.truncate(size);
+                Ok(bytes)
+            }
+            Err(EncodeError::UnexpectedEnd) => {
+                let mut encoder = EncoderImpl::new(SizeWriter::default(), config::standard());
+                match t.encode(&mut encoder) {
+                    Ok(()) => {
+                        let requested = encoder.into_writer().bytes_written;
+                        Err(Self::capacity_error(requested).into())
+                    }
+                    Err(_) => Err(CodecError::Serialize),
+                }
+            }
+            Err(_) => Err(CodecError::Serialize),
+        }
+    }
+
+    /// Decode the contents, encoded with bincode's standard configuration,
+    /// into a `T`, which may borrow from `self`.
+    pub fn to_bincode<'de, T>(&'de self) -> Result<T, CodecError>
+    where
+        T: BorrowDecode<'de, ()>,
+    {
+        match ::bincode::borrow_decode_from_slice(self, config::standard()) {
+            Ok((t, read)) if read == self.len() => Ok(t),
+            _ => Err(CodecError::Deserialize),
+        }
+    }
+}
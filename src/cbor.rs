@@ -0,0 +1,250 @@
+//! CBOR encoding into and decoding out of `Bytes<N>`.
+//!
+//! The encoder is pluggable: each of the `cbor` ([`serde_cbor`]),
+//! `cbor-minicbor` ([`minicbor_serde`]) and `cbor-smol` ([`cbor_smol`])
+//! features enables a [`Backend`]. The `Bytes` methods without a `_with`
+//! suffix use `DefaultBackend`, which is always `SerdeCbor`, and are only
+//! available with the `cbor` feature: enabling another backend never
+//! changes their output.
+//!
+//! All backends produce identical output for integers, byte and text
+//! strings, arrays, maps, booleans, `()` and `Option`, which covers the
+//! integer-keyed maps used by CTAP and COSE. They differ in how serde
+//! structs and enums are laid out: `SerdeCbor` keys struct fields by index
+//! (packed format), the others by name.
+//!
+//! Encoding options, such as the packed format or canonical encoding, are
+//! set with [`CborOptions`].
+//!
+//! [`serde_cbor`]: https://docs.rs/serde_cbor
+//! [`minicbor_serde`]: https://docs.rs/minicbor-serde
+//! [`cbor_smol`]: https://docs.rs/cbor-smol
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Bytes, CodecError};
+#[cfg(feature = "cbor-smol")]
+use checked::Checked;
+
+mod canonical;
+#[cfg(feature = "cbor-smol")]
+mod checked;
+mod options;
+pub use options::CborOptions;
+
+/// A serde CBOR implementation that `Bytes<N>` can encode with and decode from.
+pub trait Backend {
+    /// Serialize `t` into the start of `buffer`, returning the number of bytes written.
+    ///
+    /// Running out of space may be reported as [`CodecError::Serialize`];
+    /// `Bytes` then uses [`Backend::serialized_len`] to tell it apart from
+    /// other failures.
+    fn serialize<T: Serialize + ?Sized>(
+        &self,
+        t: &T,
+        buffer: &mut [u8],
+    ) -> Result<usize, CodecError>;
+
+    /// Length of the encoding of `t`, without writing it anywhere.
+    fn serialized_len<T: Serialize + ?Sized>(&self, t: &T) -> Result<usize, CodecError>;
+
+    /// Deserialize a `T` from exactly the whole of `buffer`.
+    fn deserialize<'de, T: Deserialize<'de>>(&self, buffer: &'de [u8]) -> Result<T, CodecError>;
+}
+
+/// The backend of the `Bytes` methods without a `_with` suffix.
+#[cfg(feature = "cbor")]
+pub type DefaultBackend = SerdeCbor;
+
+/// Discards the encoding, only counting its length.
+struct CountingWrite(usize);
+
+/// [`serde_cbor`](https://docs.rs/serde_cbor) in packed format.
+///
+/// `serde_cbor` is no longer maintained; prefer one of the other backends.
+#[cfg(feature = "cbor")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SerdeCbor;
+
+#[cfg(feature = "cbor")]
+impl serde_cbor::ser::Write for CountingWrite {
+    type Error = serde_cbor::Error;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0 = self.0.saturating_add(buf.len());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl Backend for SerdeCbor {
+    fn serialize<T: Serialize + ?Sized>(
+        &self,
+        t: &T,
+        buffer: &mut [u8],
+    ) -> Result<usize, CodecError> {
+        let writer = serde_cbor::ser::SliceWrite::new(buffer);
+        let mut ser = serde_cbor::Serializer::new(writer).packed_format();
+        t.serialize(&mut ser).map_err(|_| CodecError::Serialize)?;
+        Ok(ser.into_inner().bytes_written())
+    }
+
+    fn serialized_len<T: Serialize + ?Sized>(&self, t: &T) -> Result<usize, CodecError> {
+        let mut ser = serde_cbor::Serializer::new(CountingWrite(0)).packed_format();
+        t.serialize(&mut ser).map_err(|_| CodecError::Serialize)?;
+        Ok(ser.into_inner().0)
+    }
+
+    fn deserialize<'de, T: Deserialize<'de>>(&self, buffer: &'de [u8]) -> Result<T, CodecError> {
+        serde_cbor::de::from_slice_with_scratch(buffer, &mut [])
+            .map_err(|_| CodecError::Deserialize)
+    }
+}
+
+/// [`minicbor-serde`](https://docs.rs/minicbor-serde), with `()` encoded as `null`.
+#[cfg(feature = "cbor-minicbor")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Minicbor;
+
+#[cfg(feature = "cbor-minicbor")]
+impl minicbor::encode::Write for CountingWrite {
+    type Error = core::convert::Infallible;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0 = self.0.saturating_add(buf.len());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cbor-minicbor")]
+impl Backend for Minicbor {
+    fn serialize<T: Serialize + ?Sized>(
+        &self,
+        t: &T,
+        buffer: &mut [u8],
+    ) -> Result<usize, CodecError> {
+        let mut ser = minicbor_serde::Serializer::new(minicbor::encode::write::Cursor::new(buffer));
+        ser.serialize_unit_as_null(true);
+        t.serialize(&mut ser).map_err(|_| CodecError::Serialize)?;
+        Ok(ser.into_encoder().into_writer().position())
+    }
+
+    fn serialized_len<T: Serialize + ?Sized>(&self, t: &T) -> Result<usize, CodecError> {
+        let mut ser = minicbor_serde::Serializer::new(CountingWrite(0));
+        ser.serialize_unit_as_null(true);
+        t.serialize(&mut ser).map_err(|_| CodecError::Serialize)?;
+        Ok(ser.into_encoder().into_writer().0)
+    }
+
+    fn deserialize<'de, T: Deserialize<'de>>(&self, buffer: &'de [u8]) -> Result<T, CodecError> {
+        let mut de = minicbor_serde::Deserializer::new(buffer);
+        let t = T::deserialize(&mut de).map_err(|_| CodecError::Deserialize)?;
+        if de.decoder().position() != buffer.len() {
+            return Err(CodecError::Deserialize);
+        }
+        Ok(t)
+    }
+}
+
+/// [`cbor-smol`](https://docs.rs/cbor-smol).
+///
+/// `cbor-smol` panics on floating point numbers and on `collect_str`, so
+/// they are rejected with [`CodecError::Serialize`] before reaching it.
+#[cfg(feature = "cbor-smol")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CborSmol;
+
+#[cfg(feature = "cbor-smol")]
+impl cbor_smol::ser::Writer for CountingWrite {
+    type Error = cbor_smol::Error;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0 = self.0.saturating_add(buf.len());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cbor-smol")]
+impl Backend for CborSmol {
+    fn serialize<T: Serialize + ?Sized>(
+        &self,
+        t: &T,
+        buffer: &mut [u8],
+    ) -> Result<usize, CodecError> {
+        cbor_smol::cbor_serialize_to(&Checked(t), buffer).map_err(|_| CodecError::Serialize)
+    }
+
+    fn serialized_len<T: Serialize + ?Sized>(&self, t: &T) -> Result<usize, CodecError> {
+        cbor_smol::cbor_serialize_to(&Checked(t), CountingWrite(0))
+            .map_err(|_| CodecError::Serialize)
+    }
+
+    fn deserialize<'de, T: Deserialize<'de>>(&self, buffer: &'de [u8]) -> Result<T, CodecError> {
+        match cbor_smol::de::take_from_bytes(buffer) {
+            Ok((t, [])) => Ok(t),
+            _ => Err(CodecError::Deserialize),
+        }
+    }
+}
+
+impl<const N: usize> Bytes<N> {
+    #[cfg(feature = "cbor")]
+    /// Serialize `t` as CBOR into a new `Bytes<N>`, using the [`DefaultBackend`].
+    pub fn try_from_serialized<T>(t: &T) -> Result<Self, CodecError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Self::try_from_serialized_with(t, &DefaultBackend::default())
+    }
+
+    /// Serialize `t` as CBOR into a new `Bytes<N>`, using `backend`.
+    pub fn try_from_serialized_with<T, B>(t: &T, backend: &B) -> Result<Self, CodecError>
+    where
+        T: Serialize + ?Sized,
+        B: Backend,
+    {
+        let mut bytes = Self::new();
+        bytes.resize_to_capacity();
+
+        match backend.serialize(t, &mut bytes) {
+            Ok(size) => {
+                bytes.truncate(size);
+                Ok(bytes)
+            }
+            Err(CodecError::Capacity(error)) => Err(error.into()),
+            Err(error) => match backend.serialized_len(t) {
+                Ok(requested) if requested > N => Err(Self::capacity_error(requested).into()),
+                _ => Err(error),
+            },
+        }
+    }
+
+    #[cfg(feature = "cbor")]
+    /// Serialize `t` as CBOR into a new `Bytes<N>`.
+    #[deprecated(note = "use `try_from_serialized`")]
+    pub fn from_serialized<T>(t: &T) -> Result<Self, CodecError>
+    where
+        T: Serialize + ?Sized,
+    {
+        Self::try_from_serialized(t)
+    }
+
+    #[cfg(feature = "cbor")]
+    /// Deserialize the CBOR contents into a `T`, which may borrow from `self`,
+    /// using the [`DefaultBackend`].
+    pub fn to_deserialized<'de, T>(&'de self) -> Result<T, CodecError>
+    where
+        T: Deserialize<'de>,
+    {
+        self.to_deserialized_with(&DefaultBackend::default())
+    }
+
+    /// Deserialize the CBOR contents into a `T`, using `backend`.
+    pub fn to_deserialized_with<'de, T, B>(&'de self, backend: &B) -> Result<T, CodecError>
+    where
+        T: Deserialize<'de>,
+        B: Backend,
+    {
+        backend.deserialize(self)
+    }
+}
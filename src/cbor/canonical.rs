@@ -0,0 +1,303 @@
+//! In-place rewrite into the core deterministic encoding of RFC 8949,
+//! section 4.2.1, without allocating.
+
+use core::cmp::Ordering;
+
+use crate::{CapacityError, CodecError};
+
+const INDEFINITE: u8 = 31;
+const BREAK: u8 = 0xff;
+
+/// Rewrites the single CBOR item in `buffer[..len]`, returning its new length.
+///
+/// The rest of `buffer` is spare room, needed when definite lengths turn out
+/// longer than the indefinite encoding they replace.
+pub(super) fn canonicalize(buffer: &mut [u8], len: usize) -> Result<usize, CodecError> {
+    let mut rewriter = Rewriter { buffer, len };
+    let end = rewriter.item(0)?;
+    if end != rewriter.len {
+        return Err(CodecError::Serialize);
+    }
+    Ok(rewriter.len)
+}
+
+/// The initial byte and argument of a data item.
+struct Head {
+    major: u8,
+    info: u8,
+    arg: u64,
+    len: usize,
+}
+
+struct Rewriter<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl Rewriter<'_> {
+    fn byte(&self, pos: usize) -> Result<u8, CodecError> {
+        if pos < self.len {
+            Ok(self.buffer[pos])
+        } else {
+            Err(CodecError::Serialize)
+        }
+    }
+
+    fn head(&self, pos: usize) -> Result<Head, CodecError> {
+        let initial = self.byte(pos)?;
+        let info = initial & 0x1f;
+        let size = match info {
+            0..=23 | INDEFINITE => 0,
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => return Err(CodecError::Serialize),
+        };
+        let mut arg = if info < 24 { u64::from(info) } else { 0 };
+        for i in 1..=size {
+            arg = arg << 8 | u64::from(self.byte(pos + i)?);
+        }
+        Ok(Head {
+            major: initial >> 5,
+            info,
+            arg,
+            len: 1 + size,
+        })
+    }
+
+    /// Replaces `buffer[start..end]` with `bytes`, moving everything after it.
+    fn splice(&mut self, start: usize, end: usize, bytes: &[u8]) -> Result<(), CodecError> {
+        let len = self.len - (end - start) + bytes.len();
+        if len > self.buffer.len() {
+            return Err(CapacityError {
+                capacity: self.buffer.len(),
+                requested: len,
+            }
+            .into());
+        }
+        self.buffer.copy_within(end..self.len, start + bytes.len());
+        self.buffer[start..][..bytes.len()].copy_from_slice(bytes);
+        self.len = len;
+        Ok(())
+    }
+
+    /// Rewrites the head at `pos` in shortest form, returning its new length.
+    fn shorten(&mut self, pos: usize, head: &Head) -> Result<usize, CodecError> {
+        let mut encoded = [0; 9];
+        let len = encode_head(head.major, head.arg, &mut encoded);
+        if len != head.len {
+            self.splice(pos, pos + head.len, &encoded[..len])?;
+        }
+        Ok(len)
+    }
+
+    /// Rewrites the item at `pos`, returning where it now ends.
+    fn item(&mut self, pos: usize) -> Result<usize, CodecError> {
+        let head = self.head(pos)?;
+        match (head.major, head.info) {
+            (0 | 1, _) => Ok(pos + self.shorten(pos, &head)?),
+            (2 | 3, INDEFINITE) => Err(CodecError::Serialize),
+            (2 | 3, _) => {
+                let start = pos + self.shorten(pos, &head)?;
+                let end = usize::try_from(head.arg)
+                    .ok()
+                    .and_then(|len| start.checked_add(len))
+                    .filter(|&end| end <= self.len);
+                end.ok_or(CodecError::Serialize)
+            }
+            (4 | 5, _) => self.container(pos, &head),
+            (6, _) => {
+                let start = pos + self.shorten(pos, &head)?;
+                self.item(start)
+            }
+            (7, 25..=27) => self.float(pos, &head),
+            (7, INDEFINITE) => Err(CodecError::Serialize),
+            (7, _) => Ok(pos + head.len),
+            _ => Err(CodecError::Serialize),
+        }
+    }
+
+    fn container(&mut self, pos: usize, head: &Head) -> Result<usize, CodecError> {
+        let per_entry = if head.major == 5 { 2 } else { 1 };
+        let (start, end) = if head.info == INDEFINITE {
+            let mut end = pos + 1;
+            let mut count = 0;
+            while self.byte(end)? != BREAK {
+                end = self.item(end)?;
+                count += 1;
+            }
+            if count % per_entry != 0 {
+                return Err(CodecError::Serialize);
+            }
+            // drop the break, then make the length definite
+            self.splice(end, end + 1, &[])?;
+            let mut encoded = [0; 9];
+            let len = encode_head(head.major, count / per_entry, &mut encoded);
+            self.splice(pos, pos + 1, &encoded[..len])?;
+            (pos + len, end - 1 + len)
+        } else {
+            let start = pos + self.shorten(pos, head)?;
+            let mut end = start;
+            for _ in 0..head.arg.saturating_mul(per_entry) {
+                end = self.item(end)?;
+            }
+            (start, end)
+        };
+        if head.major == 5 {
+            self.sort_map(start, end)?;
+        }
+        Ok(end)
+    }
+
+    /// Insertion sort of the (already canonical) entries in `start..end` by key.
+    fn sort_map(&mut self, start: usize, end: usize) -> Result<(), CodecError> {
+        let mut sorted = start;
+        while sorted < end {
+            let key_end = self.skip(sorted)?;
+            let entry_end = self.skip(key_end)?;
+
+            // find the first sorted entry with a greater key
+            let mut insert_at = start;
+            while insert_at < sorted {
+                let other_key_end = self.skip(insert_at)?;
+                let other_key = &self.buffer[insert_at..other_key_end];
+                match other_key.cmp(&self.buffer[sorted..key_end]) {
+                    Ordering::Less => insert_at = self.skip(other_key_end)?,
+                    Ordering::Equal => return Err(CodecError::Serialize),
+                    Ordering::Greater => break,
+                }
+            }
+
+            self.buffer[insert_at..entry_end].rotate_right(entry_end - sorted);
+            sorted = entry_end;
+        }
+        Ok(())
+    }
+
+    /// Where the canonical item at `pos` ends.
+    fn skip(&self, pos: usize) -> Result<usize, CodecError> {
+        let head = self.head(pos)?;
+        let mut end = pos + head.len;
+        match head.major {
+            2 | 3 => {
+                end = usize::try_from(head.arg)
+                    .ok()
+                    .and_then(|len| end.checked_add(len))
+                    .filter(|&end| end <= self.len)
+                    .ok_or(CodecError::Serialize)?;
+            }
+            4 | 5 => {
+                let per_entry = if head.major == 5 { 2 } else { 1 };
+                for _ in 0..head.arg.saturating_mul(per_entry) {
+                    end = self.skip(end)?;
+                }
+            }
+            6 => end = self.skip(end)?,
+            _ => {}
+        }
+        Ok(end)
+    }
+
+    fn float(&mut self, pos: usize, head: &Head) -> Result<usize, CodecError> {
+        let value = match head.info {
+            25 => f64::from(f16_to_f32(head.arg as u16)),
+            26 => f64::from(f32::from_bits(head.arg as u32)),
+            _ => f64::from_bits(head.arg),
+        };
+        let mut encoded = [0; 9];
+        let len = encode_float(value, &mut encoded);
+        self.splice(pos, pos + head.len, &encoded[..len])?;
+        Ok(pos + len)
+    }
+}
+
+/// Writes the shortest head for `major` and `arg`, returning its length.
+fn encode_head(major: u8, arg: u64, out: &mut [u8; 9]) -> usize {
+    let major = major << 5;
+    if arg < 24 {
+        out[0] = major | arg as u8;
+        1
+    } else if arg <= 0xff {
+        out[0] = major | 24;
+        out[1] = arg as u8;
+        2
+    } else if arg <= 0xffff {
+        out[0] = major | 25;
+        out[1..3].copy_from_slice(&(arg as u16).to_be_bytes());
+        3
+    } else if arg <= 0xffff_ffff {
+        out[0] = major | 26;
+        out[1..5].copy_from_slice(&(arg as u32).to_be_bytes());
+        5
+    } else {
+        out[0] = major | 27;
+        out[1..9].copy_from_slice(&arg.to_be_bytes());
+        9
+    }
+}
+
+/// Writes the shortest float that keeps `value` exact, returning its length.
+fn encode_float(value: f64, out: &mut [u8; 9]) -> usize {
+    if value.is_nan() {
+        out[..3].copy_from_slice(&[0xf9, 0x7e, 0x00]);
+        return 3;
+    }
+    let single = value as f32;
+    if f64::from(single) != value {
+        out[0] = 0xfb;
+        out[1..9].copy_from_slice(&value.to_bits().to_be_bytes());
+        return 9;
+    }
+    match f32_to_f16(single) {
+        Some(half) => {
+            out[0] = 0xf9;
+            out[1..3].copy_from_slice(&half.to_be_bytes());
+            3
+        }
+        None => {
+            out[0] = 0xfa;
+            out[1..5].copy_from_slice(&single.to_bits().to_be_bytes());
+            5
+        }
+    }
+}
+
+/// The half-precision bits of `value`, if it is exactly representable.
+fn f32_to_f16(value: f32) -> Option<u16> {
+    let bits = value.to_bits();
+    let sign = (bits >> 16) as u16 & 0x8000;
+    let exp = (bits >> 23 & 0xff) as i32;
+    let man = bits & 0x7f_ffff;
+    match exp {
+        0xff if man == 0 => Some(sign | 0x7c00),
+        0 if man == 0 => Some(sign),
+        // NaNs are handled by the caller, single-precision subnormals are too small
+        0 | 0xff => None,
+        _ => {
+            let exp = exp - 127;
+            if (-14..=15).contains(&exp) {
+                (man & 0x1fff == 0).then(|| sign | ((exp + 15) as u16) << 10 | (man >> 13) as u16)
+            } else if (-24..-14).contains(&exp) {
+                // subnormal: value = m * 2^-24
+                let shift = -exp - 1;
+                let significand = 0x80_0000 | man;
+                (significand & ((1 << shift) - 1) == 0)
+                    .then(|| sign | (significand >> shift) as u16)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn f16_to_f32(half: u16) -> f32 {
+    let sign = u32::from(half & 0x8000) << 16;
+    let exp = u32::from(half >> 10 & 0x1f);
+    let man = u32::from(half & 0x3ff);
+    match exp {
+        0 => f32::from_bits(sign | (man as f32 / 16_777_216.0).to_bits()),
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | man << 13),
+        _ => f32::from_bits(sign | (exp + 112) << 23 | man << 13),
+    }
+}
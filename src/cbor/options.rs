@@ -0,0 +1,638 @@
+//! Encoding options layered on top of any [`Backend`].
+
+use serde::{
+    ser::{
+        SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
+    },
+    Deserialize, Serialize, Serializer,
+};
+
+use super::{canonical, Backend};
+use crate::CodecError;
+
+/// Options for serializing into `Bytes<N>`, wrapping a [`Backend`].
+///
+/// Struct fields and enum variants are written as map keys: by name, or in
+/// the packed format by index. The depth of the top-level value is 0, and
+/// every sequence, map, struct or enum variant adds one for its contents;
+/// packing can be limited to a range of depths. The same options give the
+/// same layout with every backend.
+///
+/// Tuple and struct variants are written as a map from the variant to an
+/// array or a map of its fields. As their fields arrive only after the
+/// map has been started, their `Serialize` impl runs twice: once for the
+/// variant and once for the fields.
+///
+/// In canonical mode, the output is rewritten into the core deterministic
+/// encoding of [RFC 8949, section 4.2.1]: shortest-form arguments and floats,
+/// definite lengths only, and map keys sorted by their encoding. Maps with
+/// duplicate keys are rejected.
+///
+/// The options only apply to serialization; deserialization is left to the
+/// wrapped backend, which needs to understand the chosen field layout.
+///
+/// ```
+/// # #[cfg(feature = "cbor")] {
+/// # use heapless_bytes::{Bytes, cbor::CborOptions};
+/// #[derive(serde::Serialize)]
+/// struct Point {
+///     x: u8,
+///     y: u8,
+/// }
+///
+/// let options = CborOptions::new().packed(true).canonical(true);
+/// let point = Bytes::<8>::try_from_serialized_with(&Point { x: 1, y: 2 }, &options).unwrap();
+/// assert_eq!(point, [0xa2, 0x00, 0x01, 0x01, 0x02]);
+/// # }
+/// ```
+///
+/// [RFC 8949, section 4.2.1]: https://www.rfc-editor.org/rfc/rfc8949#section-4.2.1
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CborOptions<B> {
+    backend: B,
+    packed: bool,
+    pack_from: usize,
+    pack_to: usize,
+    canonical: bool,
+}
+
+#[cfg(feature = "cbor")]
+impl CborOptions<super::DefaultBackend> {
+    /// Named fields, not canonical, using the [`DefaultBackend`](super::DefaultBackend).
+    pub fn new() -> Self {
+        Self::with_backend(super::DefaultBackend::default())
+    }
+}
+
+impl<B> CborOptions<B> {
+    /// Named fields, not canonical, using `backend`.
+    pub const fn with_backend(backend: B) -> Self {
+        Self {
+            backend,
+            packed: false,
+            pack_from: 0,
+            pack_to: usize::MAX,
+            canonical: false,
+        }
+    }
+
+    /// Key struct fields and enum variants by index instead of by name.
+    pub const fn packed(mut self, packed: bool) -> Self {
+        self.packed = packed;
+        self
+    }
+
+    /// Pack only from `depth` on, implies `packed(true)`.
+    pub const fn pack_starting_with(mut self, depth: usize) -> Self {
+        self.packed = true;
+        self.pack_from = depth;
+        self
+    }
+
+    /// Pack only up to, but excluding, `depth`, implies `packed(true)`.
+    pub const fn pack_to_depth(mut self, depth: usize) -> Self {
+        self.packed = true;
+        self.pack_to = depth;
+        self
+    }
+
+    /// Rewrite the output into the core deterministic encoding.
+    pub const fn canonical(mut self, canonical: bool) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    fn packing(&self) -> Packing {
+        if self.packed {
+            Packing {
+                from: self.pack_from,
+                to: self.pack_to,
+            }
+        } else {
+            Packing { from: 0, to: 0 }
+        }
+    }
+}
+
+impl<B: Backend> Backend for CborOptions<B> {
+    fn serialize<T: Serialize + ?Sized>(
+        &self,
+        t: &T,
+        buffer: &mut [u8],
+    ) -> Result<usize, CodecError> {
+        let packed = Packed::new(t, self.packing());
+        let len = self.backend.serialize(&packed, buffer)?;
+        if self.canonical {
+            canonical::canonicalize(buffer, len)
+        } else {
+            Ok(len)
+        }
+    }
+
+    /// In canonical mode, this is the length before the rewrite.
+    fn serialized_len<T: Serialize + ?Sized>(&self, t: &T) -> Result<usize, CodecError> {
+        self.backend.serialized_len(&Packed::new(t, self.packing()))
+    }
+
+    fn deserialize<'de, T: Deserialize<'de>>(&self, buffer: &'de [u8]) -> Result<T, CodecError> {
+        self.backend.deserialize(buffer)
+    }
+}
+
+/// Depths `from..to` are packed.
+#[derive(Clone, Copy)]
+struct Packing {
+    from: usize,
+    to: usize,
+}
+
+impl Packing {
+    fn at(self, depth: usize) -> bool {
+        self.from <= depth && depth < self.to
+    }
+}
+
+/// A value that serializes with the given packing, starting at `depth`.
+struct Packed<'a, T: ?Sized> {
+    value: &'a T,
+    depth: usize,
+    packing: Packing,
+    /// Only the fields of a tuple or struct variant.
+    body: bool,
+}
+
+impl<'a, T: ?Sized> Packed<'a, T> {
+    fn new(value: &'a T, packing: Packing) -> Self {
+        Self {
+            value,
+            depth: 0,
+            packing,
+            body: false,
+        }
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for Packed<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(Packer {
+            inner: serializer,
+            value: self.value,
+            depth: self.depth,
+            packing: self.packing,
+            body: self.body,
+        })
+    }
+}
+
+/// A struct field or enum variant identifier.
+enum Key {
+    Index(u32),
+    Name(&'static str),
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Key::Index(index) => serializer.serialize_u32(index),
+            Key::Name(name) => serializer.serialize_str(name),
+        }
+    }
+}
+
+/// Wraps a backend serializer, rewriting structs and enum variants
+/// according to the packing.
+///
+/// `value` is the value being serialized, to serialize it again for the
+/// fields of a tuple or struct variant.
+struct Packer<'a, S, T: ?Sized> {
+    inner: S,
+    value: &'a T,
+    depth: usize,
+    packing: Packing,
+    body: bool,
+}
+
+impl<'a, S, T: ?Sized> Packer<'a, S, T> {
+    fn key(&self, index: u32, name: &'static str) -> Key {
+        if self.packing.at(self.depth) {
+            Key::Index(index)
+        } else {
+            Key::Name(name)
+        }
+    }
+
+    fn same<'b, U: ?Sized>(&self, value: &'b U) -> Packed<'b, U> {
+        Packed {
+            value,
+            depth: self.depth,
+            packing: self.packing,
+            body: false,
+        }
+    }
+
+    /// The fields of the tuple or struct variant being serialized.
+    fn body(&self) -> Packed<'a, T> {
+        Packed {
+            value: self.value,
+            depth: self.depth + 1,
+            packing: self.packing,
+            body: true,
+        }
+    }
+}
+
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method(self, $($arg: $ty),*) -> Result<S::Ok, S::Error> {
+                self.inner.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer, T: Serialize + ?Sized> Serializer for Packer<'_, S, T> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<S::SerializeSeq>;
+    type SerializeTuple = Compound<S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = TupleVariant<S::SerializeMap, S::SerializeTuple>;
+    type SerializeMap = Compound<S::SerializeMap>;
+    type SerializeStruct = Struct<S::SerializeMap>;
+    type SerializeStructVariant = StructVariant<S::SerializeMap>;
+
+    forward! {
+        serialize_bool(v: bool);
+        serialize_i8(v: i8);
+        serialize_i16(v: i16);
+        serialize_i32(v: i32);
+        serialize_i64(v: i64);
+        serialize_i128(v: i128);
+        serialize_u8(v: u8);
+        serialize_u16(v: u16);
+        serialize_u32(v: u32);
+        serialize_u64(v: u64);
+        serialize_u128(v: u128);
+        serialize_f32(v: f32);
+        serialize_f64(v: f64);
+        serialize_char(v: char);
+        serialize_str(v: &str);
+        serialize_bytes(v: &[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(name: &'static str);
+    }
+
+    fn serialize_some<U: Serialize + ?Sized>(self, value: &U) -> Result<S::Ok, S::Error> {
+        let value = self.same(value);
+        self.inner.serialize_some(&value)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.key(variant_index, variant).serialize(self.inner)
+    }
+
+    fn serialize_newtype_struct<U: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &U,
+    ) -> Result<S::Ok, S::Error> {
+        let value = self.same(value);
+        self.inner.serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<U: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &U,
+    ) -> Result<S::Ok, S::Error> {
+        let key = self.key(variant_index, variant);
+        let value = Packed {
+            value,
+            depth: self.depth + 1,
+            packing: self.packing,
+            body: false,
+        };
+        let mut map = self.inner.serialize_map(Some(1))?;
+        map.serialize_entry(&key, &value)?;
+        map.end()
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let Packer {
+            inner,
+            depth,
+            packing,
+            ..
+        } = self;
+        Ok(Compound::new(inner.serialize_seq(len)?, depth, packing))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        let Packer {
+            inner,
+            depth,
+            packing,
+            ..
+        } = self;
+        Ok(Compound::new(inner.serialize_tuple(len)?, depth, packing))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let Packer {
+            inner,
+            depth,
+            packing,
+            ..
+        } = self;
+        Ok(Compound::new(
+            inner.serialize_tuple_struct(name, len)?,
+            depth,
+            packing,
+        ))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        if self.body {
+            let Packer {
+                inner,
+                depth,
+                packing,
+                ..
+            } = self;
+            return Ok(TupleVariant::Body(Compound::new(
+                inner.serialize_tuple(len)?,
+                depth,
+                packing,
+            )));
+        }
+        let key = self.key(variant_index, variant);
+        let body = self.body();
+        let mut map = self.inner.serialize_map(Some(1))?;
+        map.serialize_entry(&key, &body)?;
+        Ok(TupleVariant::Variant(map))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let Packer {
+            inner,
+            depth,
+            packing,
+            ..
+        } = self;
+        Ok(Compound::new(inner.serialize_map(len)?, depth, packing))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let Packer {
+            inner,
+            depth,
+            packing,
+            ..
+        } = self;
+        Ok(Struct {
+            compound: Compound::new(inner.serialize_map(Some(len))?, depth, packing),
+            packed: packing.at(depth),
+            index: 0,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        if self.body {
+            return self.serialize_struct("", len).map(StructVariant::Body);
+        }
+        let key = self.key(variant_index, variant);
+        let body = self.body();
+        let mut map = self.inner.serialize_map(Some(1))?;
+        map.serialize_entry(&key, &body)?;
+        Ok(StructVariant::Variant(map))
+    }
+
+    fn collect_str<U: core::fmt::Display + ?Sized>(self, value: &U) -> Result<S::Ok, S::Error> {
+        self.inner.collect_str(value)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// The contents of a compound value, one level deeper.
+struct Compound<C> {
+    inner: C,
+    depth: usize,
+    packing: Packing,
+}
+
+impl<C> Compound<C> {
+    fn new(inner: C, depth: usize, packing: Packing) -> Self {
+        Self {
+            inner,
+            depth: depth + 1,
+            packing,
+        }
+    }
+
+    fn nested<'a, T: ?Sized>(&self, value: &'a T) -> Packed<'a, T> {
+        Packed {
+            value,
+            depth: self.depth,
+            packing: self.packing,
+            body: false,
+        }
+    }
+}
+
+impl<C: SerializeSeq> SerializeSeq for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeTuple> SerializeTuple for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeTupleStruct> SerializeTupleStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeMap> SerializeMap for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), C::Error> {
+        let key = self.nested(key);
+        self.inner.serialize_key(&key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_value(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+/// A struct, written as a map keyed by field name or index.
+struct Struct<M> {
+    compound: Compound<M>,
+    packed: bool,
+    index: u32,
+}
+
+impl<M: SerializeMap> SerializeStruct for Struct<M> {
+    type Ok = M::Ok;
+    type Error = M::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), M::Error> {
+        let key = if self.packed {
+            Key::Index(self.index)
+        } else {
+            Key::Name(key)
+        };
+        self.index += 1;
+        let value = self.compound.nested(value);
+        self.compound.inner.serialize_entry(&key, &value)
+    }
+
+    // skipped fields keep their index, as in `serde_cbor`
+    fn skip_field(&mut self, _key: &'static str) -> Result<(), M::Error> {
+        self.index += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<M::Ok, M::Error> {
+        self.compound.inner.end()
+    }
+}
+
+/// A tuple variant: the map from the variant to its fields, which were
+/// already written, or when serializing again, the array of fields.
+enum TupleVariant<M, T> {
+    Variant(M),
+    Body(Compound<T>),
+}
+
+impl<M, T> SerializeTupleVariant for TupleVariant<M, T>
+where
+    M: SerializeMap,
+    T: SerializeTuple<Ok = M::Ok, Error = M::Error>,
+{
+    type Ok = M::Ok;
+    type Error = M::Error;
+
+    fn serialize_field<U: Serialize + ?Sized>(&mut self, value: &U) -> Result<(), M::Error> {
+        match self {
+            TupleVariant::Variant(_) => Ok(()),
+            TupleVariant::Body(body) => SerializeTuple::serialize_element(body, value),
+        }
+    }
+
+    fn end(self) -> Result<M::Ok, M::Error> {
+        match self {
+            TupleVariant::Variant(map) => map.end(),
+            TupleVariant::Body(body) => SerializeTuple::end(body),
+        }
+    }
+}
+
+/// A struct variant: the map from the variant to its fields, which were
+/// already written, or when serializing again, the map of fields.
+enum StructVariant<M> {
+    Variant(M),
+    Body(Struct<M>),
+}
+
+impl<M: SerializeMap> SerializeStructVariant for StructVariant<M> {
+    type Ok = M::Ok;
+    type Error = M::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), M::Error> {
+        match self {
+            StructVariant::Variant(_) => Ok(()),
+            StructVariant::Body(body) => body.serialize_field(key, value),
+        }
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), M::Error> {
+        match self {
+            StructVariant::Variant(_) => Ok(()),
+            StructVariant::Body(body) => body.skip_field(key),
+        }
+    }
+
+    fn end(self) -> Result<M::Ok, M::Error> {
+        match self {
+            StructVariant::Variant(map) => map.end(),
+            StructVariant::Body(body) => body.end(),
+        }
+    }
+}
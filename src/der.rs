@@ -0,0 +1,187 @@
+//! ASN.1 DER encoding of `Bytes<N>`, using the RustCrypto [`der`](https://docs.rs/der) crate.
+//!
+//! `Bytes<N>` itself is an OCTET STRING. Wrap it in [`BitStringBytes`] or
+//! [`UintBytes`] to encode it as a BIT STRING or an unsigned INTEGER.
+
+use core::{
+    cmp::Ordering,
+    ops::{Deref, DerefMut},
+};
+
+use ::der::{
+    asn1::{BitStringRef, OctetStringRef, UintRef},
+    Decode, DecodeValue, Encode, EncodeValue, FixedTag, Header, Length, Reader, Tag, ValueOrd,
+    Writer,
+};
+
+use crate::{Bytes, CodecError};
+
+/// Copies `slice` into a new `Bytes<N>`, reporting a length error for `tag`
+/// if it does not fit.
+fn copy_value<const N: usize>(slice: &[u8], tag: Tag) -> ::der::Result<Bytes<N>> {
+    Bytes::from_slice(slice).map_err(|_| tag.length_error())
+}
+
+impl<'a, const N: usize> DecodeValue<'a> for Bytes<N> {
+    fn decode_value<R: Reader<'a>>(reader: &mut R, header: Header) -> ::der::Result<Self> {
+        let len = usize::try_from(header.length)?;
+        let mut bytes = Self::new();
+        bytes
+            .resize_default(len)
+            .map_err(|_| Self::TAG.length_error())?;
+        reader.read_into(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl<const N: usize> EncodeValue for Bytes<N> {
+    fn value_len(&self) -> ::der::Result<Length> {
+        Length::try_from(self.len())
+    }
+
+    fn encode_value(&self, writer: &mut impl Writer) -> ::der::Result<()> {
+        writer.write(self)
+    }
+}
+
+impl<const N: usize> FixedTag for Bytes<N> {
+    const TAG: Tag = Tag::OctetString;
+}
+
+impl<const N: usize> ValueOrd for Bytes<N> {
+    fn value_cmp(&self, other: &Self) -> ::der::Result<Ordering> {
+        OctetStringRef::new(self)?.value_cmp(&OctetStringRef::new(other)?)
+    }
+}
+
+/// `Bytes<N>` encoded as an ASN.1 BIT STRING, without unused bits.
+///
+/// Bit strings whose length is not a multiple of eight, such as some
+/// `KeyUsage` flags, fail to decode.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitStringBytes<const N: usize>(pub Bytes<N>);
+
+/// `Bytes<N>` encoded as an unsigned ASN.1 INTEGER, big-endian.
+///
+/// Leading zeros are stripped when decoding, and added as needed when encoding.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UintBytes<const N: usize>(pub Bytes<N>);
+
+impl<const N: usize> BitStringBytes<N> {
+    fn to_ref(&self) -> ::der::Result<BitStringRef<'_>> {
+        BitStringRef::from_bytes(&self.0)
+    }
+}
+
+impl<'a, const N: usize> DecodeValue<'a> for BitStringBytes<N> {
+    fn decode_value<R: Reader<'a>>(reader: &mut R, header: Header) -> ::der::Result<Self> {
+        let value = BitStringRef::decode_value(reader, header)?;
+        let bytes = value.as_bytes().ok_or_else(|| Self::TAG.value_error())?;
+        copy_value(bytes, Self::TAG).map(Self)
+    }
+}
+
+impl<const N: usize> UintBytes<N> {
+    fn to_ref(&self) -> ::der::Result<UintRef<'_>> {
+        UintRef::new(&self.0)
+    }
+}
+
+impl<'a, const N: usize> DecodeValue<'a> for UintBytes<N> {
+    fn decode_value<R: Reader<'a>>(reader: &mut R, header: Header) -> ::der::Result<Self> {
+        let value = UintRef::decode_value(reader, header)?;
+        copy_value(value.as_bytes(), Self::TAG).map(Self)
+    }
+}
+
+macro_rules! impl_adapter {
+    ($name:ident, $tag:expr) => {
+        impl<const N: usize> From<Bytes<N>> for $name<N> {
+            fn from(bytes: Bytes<N>) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl<const N: usize> From<$name<N>> for Bytes<N> {
+            fn from(bytes: $name<N>) -> Self {
+                bytes.0
+            }
+        }
+
+        impl<const N: usize> Deref for $name<N> {
+            type Target = Bytes<N>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<const N: usize> DerefMut for $name<N> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl<const N: usize> AsRef<[u8]> for $name<N> {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl<const N: usize> EncodeValue for $name<N> {
+            fn value_len(&self) -> ::der::Result<Length> {
+                self.to_ref()?.value_len()
+            }
+
+            fn encode_value(&self, writer: &mut impl Writer) -> ::der::Result<()> {
+                self.to_ref()?.encode_value(writer)
+            }
+        }
+
+        impl<const N: usize> FixedTag for $name<N> {
+            const TAG: Tag = $tag;
+        }
+
+        impl<const N: usize> ValueOrd for $name<N> {
+            fn value_cmp(&self, other: &Self) -> ::der::Result<Ordering> {
+                self.to_ref()?.value_cmp(&other.to_ref()?)
+            }
+        }
+    };
+}
+
+impl_adapter!(BitStringBytes, Tag::BitString);
+impl_adapter!(UintBytes, Tag::Integer);
+
+impl<const N: usize> Bytes<N> {
+    /// Encode `t` as DER into a new `Bytes<N>`.
+    pub fn try_from_der<T>(t: &T) -> Result<Self, CodecError>
+    where
+        T: Encode + ?Sized,
+    {
+        let requested = t
+            .encoded_len()
+            .and_then(usize::try_from)
+            .map_err(|_| CodecError::Serialize)?;
+        if requested > N {
+            return Err(Self::capacity_error(requested).into());
+        }
+
+        let mut bytes = Self::new();
+        bytes.resize_to_capacity();
+        let size = t
+            .encode_to_slice(&mut bytes)
+            .map_err(|_| CodecError::Serialize)?
+            .len();
+        bytes.truncate(size);
+        Ok(bytes)
+    }
+
+    /// Decode the DER contents into a `T`, which may borrow from `self`.
+    pub fn to_der<'a, T>(&'a self) -> Result<T, CodecError>
+    where
+        T: Decode<'a>,
+    {
+        T::from_der(self).map_err(|_| CodecError::Deserialize)
+    }
+}
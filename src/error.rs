@@ -0,0 +1,165 @@
+//! Error types returned by fallible `Bytes<N>` operations.
+
+use core::fmt;
+
+/// The operation would need more than the `N` bytes a `Bytes<N>` can hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CapacityError {
+    /// Capacity of the target buffer.
+    pub capacity: usize,
+    /// Length the operation would have resulted in.
+    pub requested: usize,
+}
+
+/// An index was out of range for the current contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IndexError {
+    /// The offending index.
+    pub index: usize,
+    /// Length of the buffer at the time of the call.
+    pub len: usize,
+}
+
+/// Error returned by the helpers that encode values into `Bytes<N>` and
+/// decode them out of it, such as CBOR, postcard, JSON, bincode and DER.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CodecError {
+    /// The encoding needs more bytes than the buffer holds.
+    ///
+    /// `requested` is the full length of the encoding, unless the helper
+    /// documents otherwise.
+    Capacity(CapacityError),
+    /// The value could not be encoded.
+    Serialize,
+    /// The contents are not a valid encoding of the requested type, or are
+    /// followed by trailing bytes.
+    Deserialize,
+}
+
+/// Any error returned by a `Bytes<N>` operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+    Capacity(CapacityError),
+    Index(IndexError),
+}
+
+/// Error returned by `Bytes::try_from`: either the callback failed, or it
+/// reported writing more bytes than the buffer holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TryFromError<E> {
+    Capacity(CapacityError),
+    Inner(E),
+}
+
+/// A failed insertion, handing back the value that could not be inserted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InsertError<T> {
+    /// Why the insertion failed.
+    pub error: Error,
+    /// The byte or slice that was not inserted.
+    pub value: T,
+}
+
+impl<T> InsertError<T> {
+    /// Drops the value, keeping only the cause.
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+
+    /// Drops the cause, keeping only the value.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl From<CapacityError> for Error {
+    fn from(error: CapacityError) -> Self {
+        Error::Capacity(error)
+    }
+}
+
+impl From<IndexError> for Error {
+    fn from(error: IndexError) -> Self {
+        Error::Index(error)
+    }
+}
+
+impl From<CapacityError> for CodecError {
+    fn from(error: CapacityError) -> Self {
+        CodecError::Capacity(error)
+    }
+}
+
+impl<E> From<CapacityError> for TryFromError<E> {
+    fn from(error: CapacityError) -> Self {
+        TryFromError::Capacity(error)
+    }
+}
+
+impl<T> From<InsertError<T>> for Error {
+    fn from(error: InsertError<T>) -> Self {
+        error.error
+    }
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "capacity exceeded: {} bytes requested, capacity is {}",
+            self.requested, self.capacity
+        )
+    }
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "index out of range: index is {} but length is {}",
+            self.index, self.len
+        )
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Capacity(error) => error.fmt(f),
+            Error::Index(error) => error.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Capacity(error) => error.fmt(f),
+            CodecError::Serialize => f.write_str("value could not be encoded"),
+            CodecError::Deserialize => f.write_str("invalid encoding for the requested type"),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for TryFromError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryFromError::Capacity(error) => error.fmt(f),
+            TryFromError::Inner(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<T> fmt::Display for InsertError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "insertion failed: {}", self.error)
+    }
+}
+
+impl core::error::Error for CapacityError {}
+impl core::error::Error for IndexError {}
+impl core::error::Error for CodecError {}
+impl core::error::Error for Error {}
+impl<E: fmt::Debug + fmt::Display> core::error::Error for TryFromError<E> {}
+impl<T: fmt::Debug> core::error::Error for InsertError<T> {}
@@ -0,0 +1,68 @@
+//! Formatting text into `Bytes<N>`.
+
+use core::fmt;
+
+use crate::{Bytes, CapacityError};
+
+/// Appends to `bytes` while the output fits, and counts all of it, so that
+/// an overflow can report its length without formatting a second time.
+struct Counting<'a, const N: usize> {
+    bytes: &'a mut Bytes<N>,
+    requested: usize,
+}
+
+impl<const N: usize> fmt::Write for Counting<'_, N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.requested = self.requested.saturating_add(s.len());
+        if self.requested <= N {
+            self.bytes.bytes.extend_from_slice(s.as_bytes()).ok();
+        }
+        Ok(())
+    }
+}
+
+/// Appends UTF-8 text, failing with [`fmt::Error`] when the buffer is full.
+///
+/// A failed `write!` leaves the buffer as it was before the call, not with
+/// the part of the output that did fit.
+impl<const N: usize> fmt::Write for Bytes<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.bytes
+            .extend_from_slice(s.as_bytes())
+            .map_err(|_| fmt::Error)
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        let len = self.len();
+        fmt::write(self, args).inspect_err(|_| self.truncate(len))
+    }
+}
+
+impl<const N: usize> Bytes<N> {
+    /// Format `args` into a new `Bytes<N>`, see [`format_bytes!`](crate::format_bytes).
+    pub fn try_from_fmt(args: fmt::Arguments<'_>) -> Result<Self, CapacityError> {
+        let mut bytes = Self::new();
+        let mut counting = Counting {
+            bytes: &mut bytes,
+            requested: 0,
+        };
+        let result = fmt::write(&mut counting, args);
+        let requested = counting.requested;
+        if result.is_ok() && requested <= N {
+            Ok(bytes)
+        } else {
+            Err(Self::capacity_error(requested))
+        }
+    }
+}
+
+/// Formats text into a new `Bytes<N>`, like `format!`.
+///
+/// `format_bytes!(16; "AT+CSQ={}\r\n", x)` returns a
+/// `Result<Bytes<16>, CapacityError>`.
+#[macro_export]
+macro_rules! format_bytes {
+    ($n:expr; $($arg:tt)*) => {
+        $crate::Bytes::<$n>::try_from_fmt(::core::format_args!($($arg)*))
+    };
+}
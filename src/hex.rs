@@ -0,0 +1,283 @@
+//! Hex encoding and decoding of `Bytes<N>`, and `HexBytes<N>` for
+//! human-readable serde formats.
+
+use core::{
+    fmt::{self, Write as _},
+    ops::{Deref, DerefMut},
+};
+
+use heapless::{String, Vec};
+use serde::{
+    de::{Deserialize, Deserializer, Unexpected, Visitor},
+    ser::{Serialize, Serializer},
+};
+
+use crate::{Bytes, CapacityError};
+
+pub(crate) const DIGITS: &[u8; 16] = b"0123456789abcdef";
+const UPPER_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Passes the hex digits of `bytes` to `write`, a chunk at a time, so that
+/// both `core::fmt` and `ufmt` can use it.
+pub(crate) fn write_hex<E>(
+    bytes: &[u8],
+    digits: &[u8; 16],
+    mut write: impl FnMut(&str) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut buffer = [0u8; 64];
+    for chunk in bytes.chunks(buffer.len() / 2) {
+        for (byte, pair) in chunk.iter().zip(buffer.chunks_exact_mut(2)) {
+            pair[0] = digits[usize::from(byte >> 4)];
+            pair[1] = digits[usize::from(byte & 0xf)];
+        }
+        // always ASCII
+        if let Ok(pairs) = core::str::from_utf8(&buffer[..2 * chunk.len()]) {
+            write(pairs)?;
+        }
+    }
+    Ok(())
+}
+
+/// Displays bytes as lowercase hex digits, returned by [`Bytes::hex`].
+pub struct Hex<'a>(pub(crate) &'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(self.0, DIGITS, |s| f.write_str(s))
+    }
+}
+
+/// Error returned by [`Bytes::from_hex`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HexError {
+    /// The string has an odd number of digits.
+    OddLength,
+    /// The character at byte offset `index` is not a hex digit.
+    InvalidDigit { index: usize },
+    /// The decoded bytes do not fit.
+    Capacity(CapacityError),
+}
+
+impl From<CapacityError> for HexError {
+    fn from(error: CapacityError) -> Self {
+        HexError::Capacity(error)
+    }
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexError::OddLength => f.write_str("odd number of hex digits"),
+            HexError::InvalidDigit { index } => write!(f, "invalid hex digit at index {}", index),
+            HexError::Capacity(error) => error.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for HexError {}
+
+pub(crate) fn digit(c: u8, index: usize) -> Result<u8, HexError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(HexError::InvalidDigit { index }),
+    }
+}
+
+/// Decodes upper- or lowercase hex digits.
+pub(crate) fn decode<const N: usize>(hex: &[u8]) -> Result<Bytes<N>, HexError> {
+    if hex.len() % 2 != 0 {
+        return Err(HexError::OddLength);
+    }
+    if hex.len() / 2 > N {
+        return Err(Bytes::<N>::capacity_error(hex.len() / 2).into());
+    }
+    let mut bytes = Vec::new();
+    for (i, pair) in hex.chunks_exact(2).enumerate() {
+        let byte = digit(pair[0], 2 * i)? << 4 | digit(pair[1], 2 * i + 1)?;
+        bytes.push(byte).ok();
+    }
+    Ok(Bytes::from(bytes))
+}
+
+/// Decodes a hex literal for [`hex_bytes!`](crate::hex_bytes), failing
+/// compilation on odd lengths and invalid digits.
+#[doc(hidden)]
+pub const fn decode_literal<const M: usize>(hex: &str) -> [u8; M] {
+    const fn digit(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => panic!("invalid hex digit"),
+        }
+    }
+
+    let hex = hex.as_bytes();
+    assert!(hex.len() == 2 * M, "odd number of hex digits");
+    let mut bytes = [0; M];
+    let mut i = 0;
+    while i < M {
+        bytes[i] = digit(hex[2 * i]) << 4 | digit(hex[2 * i + 1]);
+        i += 1;
+    }
+    bytes
+}
+
+impl<const N: usize> Bytes<N> {
+    /// Decode upper- or lowercase hex digits, without a `0x` prefix.
+    pub fn from_hex(hex: &str) -> Result<Self, HexError> {
+        decode(hex.as_bytes())
+    }
+
+    /// Encode the contents as lowercase hex digits.
+    ///
+    /// `M` must be at least `2 * N`, which is checked at compile time.
+    pub fn to_hex<const M: usize>(&self) -> String<M> {
+        const { assert!(M >= 2 * N, "String<M> is too short for the hex of Bytes<N>") };
+        let mut hex = String::new();
+        for byte in self.iter() {
+            hex.push(char::from(DIGITS[usize::from(byte >> 4)])).ok();
+            hex.push(char::from(DIGITS[usize::from(byte & 0xf)])).ok();
+        }
+        hex
+    }
+
+    /// Display the contents as lowercase hex digits, without allocating.
+    pub fn hex(&self) -> Hex<'_> {
+        Hex(self)
+    }
+}
+
+/// Writes `bytes` as hex digits, padded like an integer: `#` adds a `0x`
+/// prefix, and width, fill, alignment and the `0` flag apply to the whole.
+fn pad_hex(bytes: &[u8], digits: &[u8; 16], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let prefix = if f.alternate() { "0x" } else { "" };
+    let len = prefix.len().saturating_add(bytes.len().saturating_mul(2));
+    let padding = f.width().map_or(0, |width| width.saturating_sub(len));
+
+    if f.sign_aware_zero_pad() {
+        f.write_str(prefix)?;
+        for _ in 0..padding {
+            f.write_char('0')?;
+        }
+        return write_hex(bytes, digits, |s| f.write_str(s));
+    }
+
+    let (before, after) = match f.align() {
+        Some(fmt::Alignment::Left) => (0, padding),
+        Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        Some(fmt::Alignment::Right) | None => (padding, 0),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    f.write_str(prefix)?;
+    write_hex(bytes, digits, |s| f.write_str(s))?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+/// Hex digits, padded like an integer: `{:x}`, `{:#x}`, `{:08x}`.
+impl<const N: usize> fmt::LowerHex for Bytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        pad_hex(self, DIGITS, f)
+    }
+}
+
+/// Uppercase hex digits, padded like an integer: `{:X}`, `{:#X}`, `{:08X}`.
+impl<const N: usize> fmt::UpperHex for Bytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        pad_hex(self, UPPER_DIGITS, f)
+    }
+}
+
+/// `Bytes<N>` that serializes as a lowercase hex string in human-readable
+/// formats.
+///
+/// Binary formats still use compact bytes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HexBytes<const N: usize>(pub Bytes<N>);
+
+impl<const N: usize> From<Bytes<N>> for HexBytes<N> {
+    fn from(bytes: Bytes<N>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const N: usize> From<HexBytes<N>> for Bytes<N> {
+    fn from(bytes: HexBytes<N>) -> Self {
+        bytes.0
+    }
+}
+
+impl<const N: usize> Deref for HexBytes<N> {
+    type Target = Bytes<N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for HexBytes<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for HexBytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> Serialize for HexBytes<N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&self.0.hex())
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for HexBytes<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct HexVisitor<const N: usize>;
+
+        impl<const N: usize> Visitor<'_> for HexVisitor<N> {
+            type Value = HexBytes<N>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(formatter, "a hex string of at most {} bytes", N)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                match Bytes::from_hex(v) {
+                    Ok(bytes) => Ok(HexBytes(bytes)),
+                    Err(HexError::Capacity(error)) => {
+                        Err(E::invalid_length(error.requested, &self))
+                    }
+                    Err(_) => Err(E::invalid_value(Unexpected::Str(v), &self)),
+                }
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(HexVisitor)
+        } else {
+            Bytes::deserialize(deserializer).map(HexBytes)
+        }
+    }
+}
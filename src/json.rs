@@ -0,0 +1,52 @@
+//! JSON documents in `Bytes<N>`, using [`serde-json-core`](https://docs.rs/serde-json-core).
+//!
+//! `Bytes<N>` values inside a document, and other byte strings, are written
+//! and read as lowercase hex strings. `Base64Bytes<N>` uses base64 instead,
+//! with the `base64` feature.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Bytes, CodecError};
+
+mod hexed;
+
+use hexed::{Hexed, Unhex};
+
+impl<const N: usize> Bytes<N> {
+    /// Serialize `t` as a JSON document into a new `Bytes<N>`.
+    ///
+    /// `serde-json-core` cannot measure a document without writing it, so
+    /// on overflow `requested` is only a lower bound, one more than `N`.
+    pub fn try_from_json<T>(t: &T) -> Result<Self, CodecError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut bytes = Self::new();
+        bytes.resize_to_capacity();
+
+        match serde_json_core::to_slice(&Hexed(t), &mut bytes) {
+            Ok(size) => {
+                bytes.truncate(size);
+                Ok(bytes)
+            }
+            Err(serde_json_core::ser::Error::BufferFull) => {
+                Err(Self::capacity_error(N.saturating_add(1)).into())
+            }
+            Err(_) => Err(CodecError::Serialize),
+        }
+    }
+
+    /// Deserialize the JSON document into a `T`, which may borrow from `self`.
+    ///
+    /// Borrowed strings cannot contain escape sequences. Trailing characters
+    /// other than whitespace are an error.
+    pub fn to_json<'de, T>(&'de self) -> Result<T, CodecError>
+    where
+        T: Deserialize<'de>,
+    {
+        let mut de = serde_json_core::de::Deserializer::new(self, None);
+        let t = T::deserialize(Unhex(&mut de)).map_err(|_| CodecError::Deserialize)?;
+        de.end().map_err(|_| CodecError::Deserialize)?;
+        Ok(t)
+    }
+}
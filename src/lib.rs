@@ -1,10 +1,40 @@
 //! # heapless-bytes
 //!
 //! Newtype around heapless byte Vec with efficient serde.
+//!
+//! None of the inherent methods of `Bytes<N>` panic; out-of-capacity and
+//! out-of-range calls return an error instead. This is checked in
+//! `tests/no_panic.rs`, run with `cargo test --release`.
+//!
+//! Serde formats encode `Bytes<N>` as a byte string, in every format: in
+//! `serde_json`, for example, that is an array of integers. This is
+//! deliberate. `Bytes<N>` does not switch on `is_human_readable()`, because
+//! binary formats such as `cbor-smol` claim to be human-readable and could
+//! then no longer read back what they wrote. For strings, opt in per field
+//! with the `HexBytes<N>` wrapper, which uses a hex string in formats whose
+//! `is_human_readable()` is true, or with `Base64Bytes<N>` from the `base64`
+//! feature, next to base64 and base64url encoding and decoding. The JSON
+//! helpers of the `json` feature write plain `Bytes<N>` as hex strings too.
+//! The `base32`, `base58` and `base58check` features add those encodings.
+//!
+//! The `zeroize` feature implements `Zeroize` and `ZeroizeOnDrop`: the whole
+//! buffer, including the capacity past the length, is wiped on drop. With
+//! `zeroize-on-shrink`, `truncate`, `clear`, `pop`, `remove`, `swap_remove`,
+//! `retain`, `retain_mut` and a shrinking `resize` or `resize_default` also
+//! wipe the bytes they give up. The unsafe `Vec` methods reached through
+//! `DerefMut`, `set_len`, `pop_unchecked` and `swap_remove_unchecked`, do not.
+//!
+//! It also adds `SecretBytes<N>`, whose `Debug` does not print the contents,
+//! and which only serializes with the `serialize-secrets` feature.
+//!
+//! The `subtle` feature adds constant-time `ct_eq` and conditional selection,
+//! and a `CtBytes<N>` wrapper whose `==` is constant-time. The `==` of
+//! `Bytes<N>` itself short-circuits, so do not use it on MACs or PINs.
 
 #![cfg_attr(not(test), no_std)]
 
 use core::{
+    borrow::{Borrow, BorrowMut},
     cmp::Ordering,
     fmt::{self, Debug},
     hash::{Hash, Hasher},
@@ -19,8 +49,67 @@ pub use typenum::{IsGreaterOrEqual, True, Unsigned};
 
 use heapless::Vec;
 
+mod error;
+pub use error::{CapacityError, CodecError, Error, IndexError, InsertError, TryFromError};
+
+mod format;
+mod literal;
+mod view;
+pub use view::BytesView;
+
+#[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+pub mod cbor;
+
+#[cfg(feature = "minicbor")]
+mod minicbor_traits;
+
+#[cfg(feature = "ufmt")]
+mod ufmt_traits;
+
+#[cfg(feature = "postcard")]
+pub mod postcard;
+
+#[cfg(feature = "bincode")]
+pub mod bincode;
+
+#[cfg(feature = "der")]
+pub mod der;
+
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "zeroize")]
+mod secret;
+#[cfg(feature = "zeroize")]
+mod zeroize;
+#[cfg(feature = "zeroize")]
+pub use secret::SecretBytes;
+
+#[cfg(feature = "subtle")]
+mod subtle;
+#[cfg(feature = "subtle")]
+pub use crate::subtle::CtBytes;
+
+pub mod hex;
+pub use hex::{HexBytes, HexError};
+
+#[cfg(feature = "base64")]
+pub mod base64;
+#[cfg(feature = "base64")]
+pub use base64::{Base64Bytes, Base64Error};
+
+#[cfg(feature = "base32")]
+pub mod base32;
+#[cfg(feature = "base32")]
+pub use base32::Base32Error;
+
+#[cfg(feature = "base58")]
+pub mod base58;
+#[cfg(feature = "base58")]
+pub use base58::Base58Error;
+
 use serde::{
-    de::{Deserialize, Deserializer, Visitor},
+    de::{Deserialize, Deserializer, Error as _, SeqAccess, Visitor},
     ser::{Serialize, Serializer},
 };
 
@@ -41,9 +130,9 @@ impl<const N: usize> From<Vec<u8, N>> for Bytes<N> {
 }
 
 impl<const N: usize> Bytes<N> {
-    /// Construct a new, empty `Bytes<N>`.
-    pub fn new() -> Self {
-        Bytes::from(Vec::new())
+    /// Construct a new, empty `Bytes<N>`, also in `const` and `static` items.
+    pub const fn new() -> Self {
+        Bytes { bytes: Vec::new() }
     }
 
     // /// Construct a new, empty `Bytes<N>` with the specified capacity.
@@ -59,13 +148,14 @@ impl<const N: usize> Bytes<N> {
     }
 
     /// Unwraps the Vec<u8, N>, same as `into_vec`.
-    pub fn into_inner(self) -> Vec<u8, N> {
-        self.bytes
+    pub fn into_inner(mut self) -> Vec<u8, N> {
+        // `Bytes` implements `Drop` with the `zeroize` feature
+        core::mem::take(&mut self.bytes)
     }
 
     /// Unwraps the Vec<u8, N>, same as `into_inner`.
     pub fn into_vec(self) -> Vec<u8, N> {
-        self.bytes
+        self.into_inner()
     }
 
     /// Returns an immutable slice view.
@@ -83,7 +173,7 @@ impl<const N: usize> Bytes<N> {
     /// Low-noise conversion between lengths.
     ///
     /// We can't implement TryInto since it would clash with blanket implementations.
-    pub fn try_convert_into<const M: usize>(&self) -> Result<Bytes<M>, ()> {
+    pub fn try_convert_into<const M: usize>(&self) -> Result<Bytes<M>, CapacityError> {
         Bytes::<M>::from_slice(self)
     }
 
@@ -98,32 +188,41 @@ impl<const N: usize> Bytes<N> {
     //     Ok(Self::from(bytes))
     // }
 
-    pub fn from_slice(slice: &[u8]) -> core::result::Result<Self, ()> {
+    pub fn from_slice(slice: &[u8]) -> core::result::Result<Self, CapacityError> {
         let mut bytes = Vec::<u8, N>::new();
-        bytes.extend_from_slice(slice)?;
+        bytes
+            .extend_from_slice(slice)
+            .map_err(|_| Self::capacity_error(slice.len()))?;
         Ok(Self::from(bytes))
     }
 
+    pub(crate) fn capacity_error(requested: usize) -> CapacityError {
+        CapacityError {
+            capacity: N,
+            requested,
+        }
+    }
+
     /// Some APIs offer an interface of the form `f(&mut [u8]) -> Result<usize, E>`,
     /// with the contract that the Ok-value signals how many bytes were written.
     ///
     /// This constructor allows wrapping such interfaces in a more ergonomic way,
     /// returning a Bytes willed using `f`.
     ///
+    /// If `f` claims to have written more than `N` bytes, a [`CapacityError`]
+    /// is returned instead of panicking.
+    ///
     /// It seems it's not possible to do this as an actual `TryFrom` implementation.
     pub fn try_from<E>(
         f: impl FnOnce(&mut [u8]) -> core::result::Result<usize, E>
     )
-        -> core::result::Result<Self, E>
+        -> core::result::Result<Self, TryFromError<E>>
     {
         let mut data = Self::new();
         data.resize_to_capacity();
-        let result = f(&mut data);
-
-        result.map(|count| {
-            data.resize_default(count).unwrap();
-            data
-        })
+        let count = f(&mut data).map_err(TryFromError::Inner)?;
+        data.resize_default(count)?;
+        Ok(data)
     }
 
     // pub fn try_from<'a, E>(
@@ -142,12 +241,32 @@ impl<const N: usize> Bytes<N> {
     // }
 
     // cf. https://internals.rust-lang.org/t/add-vec-insert-slice-at-to-insert-the-content-of-a-slice-at-an-arbitrary-index/11008/3
-    pub fn insert_slice_at(&mut self, slice: &[u8], at: usize) -> core::result::Result<(), ()> {
+    pub fn insert_slice_at<'s>(
+        &mut self,
+        slice: &'s [u8],
+        at: usize,
+    ) -> core::result::Result<(), InsertError<&'s [u8]>> {
         let l = slice.len();
         let before = self.len();
 
+        if at > before {
+            return Err(InsertError {
+                error: IndexError {
+                    index: at,
+                    len: before,
+                }
+                .into(),
+                value: slice,
+            });
+        }
+
         // make space
-        self.bytes.resize_default(before + l)?;
+        if let Err(error) = self.resize_default(before.saturating_add(l)) {
+            return Err(InsertError {
+                error: error.into(),
+                value: slice,
+            });
+        }
 
         // move back existing
         let raw: &mut [u8] = &mut self.bytes;
@@ -159,21 +278,25 @@ impl<const N: usize> Bytes<N> {
         Ok(())
     }
 
-    pub fn insert(&mut self, index: usize, item: u8) -> Result<(), u8> {
-        self.insert_slice_at(&[item], index).map_err(|_| item)
+    pub fn insert(&mut self, index: usize, item: u8) -> Result<(), InsertError<u8>> {
+        self.insert_slice_at(&[item], index)
+            .map_err(|InsertError { error, .. }| InsertError { error, value: item })
     }
 
-    pub fn remove(&mut self, index: usize) -> Result<u8, ()> {
+    pub fn remove(&mut self, index: usize) -> Result<u8, IndexError> {
         if index < self.len() {
             unsafe { Ok(self.remove_unchecked(index)) }
         } else {
-            Err(())
+            Err(IndexError {
+                index,
+                len: self.len(),
+            })
         }
     }
 
     pub(crate) unsafe fn remove_unchecked(&mut self, index: usize) -> u8 {
         // the place we are taking from.
-        let p = (self.bytes.as_mut_ptr() as *mut u8).add(index);
+        let p = self.bytes.as_mut_ptr().add(index);
 
         // copy it out, unsafely having a copy of the value on
         // the stack and in the vector at the same time.
@@ -182,31 +305,135 @@ impl<const N: usize> Bytes<N> {
         // shift everything down to fill in that spot.
         ptr::copy(p.offset(1), p, self.len() - index - 1);
 
-        self.resize_default(self.len() - 1).unwrap();
+        let new_len = self.len() - 1;
+        self.wipe_from(new_len);
+        self.bytes.set_len(new_len);
         ret
     }
 
-    pub fn resize_default(&mut self, new_len: usize) -> core::result::Result<(), ()> {
-        self.bytes.resize_default(new_len)
+    /// Shortens the buffer to `len` bytes, if it is longer.
+    pub fn truncate(&mut self, len: usize) {
+        self.wipe_from(len);
+        self.bytes.truncate(len);
+    }
+
+    /// Removes all bytes.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Removes the last byte and returns it, or `None` if the buffer is empty.
+    pub fn pop(&mut self) -> Option<u8> {
+        let (&last, rest) = self.bytes.split_last()?;
+        let new_len = rest.len();
+        self.truncate(new_len);
+        Some(last)
+    }
+
+    /// Removes the byte at `index` and returns it, moving the last byte into
+    /// its place.
+    pub fn swap_remove(&mut self, index: usize) -> Result<u8, IndexError> {
+        let len = self.len();
+        let Some((&last, rest)) = self.bytes.split_last() else {
+            return Err(IndexError { index, len });
+        };
+        let new_len = rest.len();
+        let Some(slot) = self.bytes.get_mut(index) else {
+            return Err(IndexError { index, len });
+        };
+        let removed = core::mem::replace(slot, last);
+        self.truncate(new_len);
+        Ok(removed)
+    }
+
+    /// Keeps only the bytes for which `f` returns `true`, in order.
+    pub fn retain(&mut self, mut f: impl FnMut(&u8) -> bool) {
+        self.retain_mut(|byte| f(byte))
+    }
+
+    /// Keeps only the bytes for which `f` returns `true`, in order, letting
+    /// `f` modify them.
+    pub fn retain_mut(&mut self, mut f: impl FnMut(&mut u8) -> bool) {
+        let raw: &mut [u8] = &mut self.bytes;
+        let mut kept = 0;
+        for read in 0..raw.len() {
+            let Some(byte) = raw.get_mut(read) else {
+                break;
+            };
+            if f(byte) {
+                let byte = *byte;
+                if let Some(slot) = raw.get_mut(kept) {
+                    *slot = byte;
+                }
+                kept += 1;
+            }
+        }
+        self.truncate(kept);
+    }
+
+    /// Wipes the bytes from `new_len` on, before a shrinking operation gives
+    /// them up. Does nothing without the `zeroize-on-shrink` feature.
+    #[inline]
+    fn wipe_from(&mut self, new_len: usize) {
+        #[cfg(feature = "zeroize-on-shrink")]
+        if let Some(vacated) = self.bytes.get_mut(new_len..) {
+            ::zeroize::Zeroize::zeroize(vacated);
+        }
+        #[cfg(not(feature = "zeroize-on-shrink"))]
+        let _ = new_len;
+    }
+
+    pub fn resize_default(&mut self, new_len: usize) -> core::result::Result<(), CapacityError> {
+        self.wipe_from(new_len);
+        self.bytes
+            .resize_default(new_len)
+            .map_err(|_| Self::capacity_error(new_len))
+    }
+
+    /// Resizes to `new_len` bytes, filling any new ones with `value`.
+    pub fn resize(&mut self, new_len: usize, value: u8) -> core::result::Result<(), CapacityError> {
+        self.wipe_from(new_len);
+        self.bytes
+            .resize(new_len, value)
+            .map_err(|_| Self::capacity_error(new_len))
     }
 
     pub fn resize_to_capacity(&mut self) {
         self.bytes.resize_default(self.bytes.capacity()).ok();
     }
 
-    // /// Clone into at least same size byte buffer.
-    // pub fn to_bytes<M>(&self) -> Bytes<M>
-    // where
-    //     M: ArrayLength<u8> + IsGreaterOrEqual<N, Output = True>,
-    // {
-    //     match Bytes::<M>::try_from_slice(self) {
-    //         Ok(byte_buf) => byte_buf,
-    //         _ => unreachable!(),
-    //     }
-    // }
+    /// Clone into at least same size byte buffer.
+    ///
+    /// `M` must be at least `N`, which is checked at compile time; narrowing
+    /// goes through the fallible [`to_bytes`](Self::to_bytes). A generic
+    /// `From<Bytes<N>> for Bytes<M>` would overlap with `From<T> for T`.
+    pub fn widen<const M: usize>(&self) -> Bytes<M> {
+        const { assert!(M >= N, "Bytes<M> is narrower than Bytes<N>") };
+        let mut bytes = Bytes::new();
+        bytes.bytes.extend_from_slice(self).ok();
+        bytes
+    }
+
+    /// Concatenate `self` and `other` into a new buffer of capacity `N + M`.
+    ///
+    /// This is not type-level concatenation, which is out of scope on stable
+    /// Rust: it cannot name `Bytes<{ N + M }>`, and a helper trait with an
+    /// `Output` type would need an impl for every pair of capacities.
+    /// Instead the caller gives `O`, and a compile-time assertion checks that
+    /// it equals `N + M`. Like other post-monomorphization errors, a wrong
+    /// `O` is reported by `cargo build`, not by `cargo check`. When chaining,
+    /// the intermediate capacity needs spelling out:
+    /// `header.concat::<_, 36>(&payload).concat::<_, 52>(&tag)`.
+    pub fn concat<const M: usize, const O: usize>(&self, other: &Bytes<M>) -> Bytes<O> {
+        const { assert!(O == N + M, "Bytes<O> is not Bytes<N + M>") };
+        let mut bytes = Bytes::new();
+        bytes.bytes.extend_from_slice(self).ok();
+        bytes.bytes.extend_from_slice(other).ok();
+        bytes
+    }
 
     /// Fallible conversion into differently sized byte buffer.
-    pub fn to_bytes<const M: usize>(&self) -> Result<Bytes<M>, ()>
+    pub fn to_bytes<const M: usize>(&self) -> Result<Bytes<M>, CapacityError>
     {
         Bytes::<M>::from_slice(self)
     }
@@ -220,28 +447,6 @@ impl<const N: usize> Bytes<N> {
     // pub fn deref_mut(&mut self) -> &mut [u8] {
     //     self.bytes.deref_mut()
     // }
-
-    #[cfg(feature = "cbor")]
-    pub fn from_serialized<T>(t: &T) -> Self
-    where
-        T: Serialize,
-    {
-        let mut vec = Vec::<u8, N>::new();
-        vec.resize_default(N).unwrap();
-        let buffer = vec.deref_mut();
-
-        let writer = serde_cbor::ser::SliceWrite::new(buffer);
-        let mut ser = serde_cbor::Serializer::new(writer)
-            .packed_format()
-            // .pack_starting_with(1)
-            // .pack_to_depth(1)
-        ;
-        t.serialize(&mut ser).unwrap();
-        let writer = ser.into_inner();
-        let size = writer.bytes_written();
-        vec.resize_default(size).unwrap();
-        Self::from(vec)
-    }
 }
 
 // impl<N, E, F> TryFrom<F> for Bytes<N>
@@ -308,17 +513,19 @@ impl<const N: usize> DerefMut for Bytes<N> {
     }
 }
 
-// impl Borrow<Bytes> for Bytes<N> {
-//     fn borrow(&self) -> &Bytes {
-//         Bytes::new(&self.bytes)
-//     }
-// }
+/// `Eq`, `Ord` and `Hash` agree with those of `[u8]`, so maps keyed by
+/// `Bytes<N>` can be queried with a `&[u8]`.
+impl<const N: usize> Borrow<[u8]> for Bytes<N> {
+    fn borrow(&self) -> &[u8] {
+        &self.bytes
+    }
+}
 
-// impl BorrowMut<Bytes> for Bytes<N> {
-//     fn borrow_mut(&mut self) -> &mut Bytes {
-//         unsafe { &mut *(&mut self.bytes as &mut [u8] as *mut [u8] as *mut Bytes) }
-//     }
-// }
+impl<const N: usize> BorrowMut<[u8]> for Bytes<N> {
+    fn borrow_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+}
 
 impl<Rhs, const N: usize> PartialEq<Rhs> for Bytes<N>
 where
@@ -338,9 +545,16 @@ where
     }
 }
 
+impl<const N: usize> Ord for Bytes<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
 impl<const N: usize> Hash for Bytes<N> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.bytes.hash(state);
+        // must match `[u8]`, for `Borrow<[u8]>`
+        self.as_slice().hash(state);
     }
 }
 
@@ -349,7 +563,7 @@ impl<const N: usize> IntoIterator for Bytes<N> {
     type IntoIter = <Vec<u8, N> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.bytes.into_iter()
+        self.into_inner().into_iter()
     }
 }
 
@@ -381,57 +595,301 @@ impl<const N: usize> Serialize for Bytes<N>
     }
 }
 
-// TODO: can we delegate to Vec<u8, N> deserialization instead of reimplementing?
-impl<'de, const N: usize> Deserialize<'de> for Bytes<N>
+/// Deserializes `Bytes<N>` from bytes, a sequence of bytes or the UTF-8 of a string.
+struct ValueVisitor<'de, const N: usize>(PhantomData<&'de ()>);
+
+impl<'de, const N: usize> Visitor<'de> for ValueVisitor<'de, N>
 {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    // type Value = Vec<T, N>;
+    type Value = Bytes<N>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "a byte string or sequence of at most {} bytes",
+            N
+        )
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
     where
-        D: Deserializer<'de>,
+        E: serde::de::Error,
     {
-        struct ValueVisitor<'de, const N: usize>(PhantomData<&'de ()>);
-
-        impl<'de, const N: usize> Visitor<'de> for ValueVisitor<'de, N>
-        {
-            // type Value = Vec<T, N>;
-            type Value = Bytes<N>;
-
-            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-                formatter.write_str("a sequence")
+        if v.len() > N {
+            // hprintln!("error! own size: {}, data size: {}", N::to_usize(), v.len()).ok();
+            // return Err(E::invalid_length(values.capacity() + 1, &self))?;
+            return Err(E::invalid_length(v.len(), &self))?;
+        }
+        let mut buf: Vec<u8, N> = Vec::new();
+        // avoid unwrapping even though redundant
+        match buf.extend_from_slice(v) {
+            Ok(()) => {}
+            Err(()) => {
+                // hprintln!("error! own size: {}, data size: {}", N::to_usize(), v.len()).ok();
+                // return Err(E::invalid_length(values.capacity() + 1, &self))?;
+                return Err(E::invalid_length(v.len(), &self))?;
             }
+        }
+        Ok(Bytes::<N>::from(buf))
+    }
 
-            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
-            where
-                E: serde::de::Error,
-            {
-                if v.len() > N {
-                    // hprintln!("error! own size: {}, data size: {}", N::to_usize(), v.len()).ok();
-                    // return Err(E::invalid_length(values.capacity() + 1, &self))?;
-                    return Err(E::invalid_length(v.len(), &self))?;
-                }
-                let mut buf: Vec<u8, N> = Vec::new();
-                // avoid unwrapping even though redundant
-                match buf.extend_from_slice(v) {
-                    Ok(()) => {}
-                    Err(()) => {
-                        // hprintln!("error! own size: {}, data size: {}", N::to_usize(), v.len()).ok();
-                        // return Err(E::invalid_length(values.capacity() + 1, &self))?;
-                        return Err(E::invalid_length(v.len(), &self))?;
-                    }
-                }
-                Ok(Bytes::<N>::from(buf))
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_bytes(v.as_bytes())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        if let Some(len) = seq.size_hint().filter(|&len| len > N) {
+            return Err(A::Error::invalid_length(len, &self));
+        }
+        let mut buf: Vec<u8, N> = Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            if buf.push(byte).is_err() {
+                return Err(A::Error::invalid_length(N.saturating_add(1), &self));
             }
         }
+        Ok(Bytes::<N>::from(buf))
+    }
+}
 
+// TODO: can we delegate to Vec<u8, N> deserialization instead of reimplementing?
+impl<'de, const N: usize> Deserialize<'de> for Bytes<N>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
         deserializer.deserialize_bytes(ValueVisitor(PhantomData))
     }
 }
 
 #[cfg(test)]
-#[cfg(feature = "cbor")]
 mod tests {
     use super::*;
 
     #[test]
+    fn test_capacity_errors() {
+        assert_eq!(
+            Bytes::<4>::from_slice(b"12345"),
+            Err(CapacityError {
+                capacity: 4,
+                requested: 5
+            })
+        );
+
+        let mut bytes = Bytes::<4>::from_slice(b"1234").unwrap();
+        assert_eq!(
+            bytes.to_bytes::<2>(),
+            Err(CapacityError {
+                capacity: 2,
+                requested: 4
+            })
+        );
+        assert_eq!(
+            bytes.resize_default(8),
+            Err(CapacityError {
+                capacity: 4,
+                requested: 8
+            })
+        );
+
+        let error = bytes.insert(0, b'0').unwrap_err();
+        assert_eq!(error.value, b'0');
+        assert_eq!(
+            Error::from(error),
+            Error::Capacity(CapacityError {
+                capacity: 4,
+                requested: 5
+            })
+        );
+        assert_eq!(bytes.insert_slice_at(b"56", 4).unwrap_err().value, b"56");
+        assert_eq!(bytes, b"1234");
+    }
+
+    #[test]
+    fn test_index_errors() {
+        let mut bytes = Bytes::<4>::from_slice(b"12").unwrap();
+        assert_eq!(bytes.remove(2), Err(IndexError { index: 2, len: 2 }));
+        assert_eq!(bytes.remove(0), Ok(b'1'));
+        assert_eq!(bytes, b"2");
+    }
+
+    #[test]
+    fn test_error_display() {
+        let error: Error = IndexError { index: 3, len: 1 }.into();
+        assert_eq!(
+            error.to_string(),
+            "index out of range: index is 3 but length is 1"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_visitors() {
+        use serde::de::{
+            value::{BorrowedBytesDeserializer, Error as ValueError, SeqDeserializer},
+            IntoDeserializer,
+        };
+
+        let bytes = BorrowedBytesDeserializer::<ValueError>::new(b"1234");
+        assert_eq!(Bytes::<4>::deserialize(bytes).unwrap(), b"1234");
+
+        let seq = SeqDeserializer::<_, ValueError>::new([1u8, 2, 3].into_iter());
+        assert_eq!(Bytes::<4>::deserialize(seq).unwrap(), b"\x01\x02\x03");
+        let seq = SeqDeserializer::<_, ValueError>::new([1u8, 2, 3].into_iter());
+        assert_eq!(
+            Bytes::<2>::deserialize(seq).unwrap_err().to_string(),
+            "invalid length 3, expected a byte string or sequence of at most 2 bytes"
+        );
+        // no size hint, so the capacity is checked element by element
+        let seq = SeqDeserializer::<_, ValueError>::new([1u8, 2, 3].into_iter().filter(|_| true));
+        assert_eq!(
+            Bytes::<2>::deserialize(seq).unwrap_err().to_string(),
+            "invalid length 3, expected a byte string or sequence of at most 2 bytes"
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Bytes<4>>("[1, 2, 3]").unwrap(),
+            b"\x01\x02\x03"
+        );
+        assert!(serde_json::from_str::<Bytes<2>>("[1, 2, 3]").is_err());
+
+        let string: serde::de::value::StrDeserializer<'_, ValueError> = "abcd".into_deserializer();
+        assert_eq!(Bytes::<4>::deserialize(string).unwrap(), b"abcd");
+    }
+
+    #[test]
+    fn test_hex_bytes() {
+        let bytes = HexBytes(Bytes::<4>::from_slice(&[0xde, 0xad, 0xbe, 0xef]).unwrap());
+        assert_eq!(serde_json::to_string(&bytes).unwrap(), r#""deadbeef""#);
+        assert_eq!(
+            serde_json::from_str::<HexBytes<4>>(r#""DEADbeef""#).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            serde_json::from_str::<HexBytes<4>>(r#""""#).unwrap(),
+            HexBytes::default()
+        );
+
+        let error = serde_json::from_str::<HexBytes<3>>(r#""deadbeef""#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid length 4, expected a hex string of at most 3 bytes at line 1 column 10"
+        );
+        assert!(serde_json::from_str::<HexBytes<4>>(r#""dead0""#).is_err());
+        assert!(serde_json::from_str::<HexBytes<4>>(r#""deadbeeg""#).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "base64")]
+    fn test_base64() {
+        let bytes = Base64Bytes(Bytes::<5>::from_slice(b"hello").unwrap());
+        assert_eq!(serde_json::to_string(&bytes).unwrap(), r#""aGVsbG8=""#);
+        assert_eq!(
+            serde_json::from_str::<Base64Bytes<5>>(r#""aGVsbG8=""#).unwrap(),
+            bytes
+        );
+
+        use base64::{encoded_len, Variant};
+
+        for (decoded, encoded) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"fooba", "Zm9vYmE="),
+            (b"foobar", "Zm9vYmFy"),
+        ] {
+            let bytes = Bytes::<6>::from_slice(decoded).unwrap();
+            let unpadded = encoded.trim_end_matches('=');
+            assert_eq!(bytes.base64(Variant::Standard).to_string(), encoded);
+            assert_eq!(bytes.to_base64::<8>(Variant::Standard), encoded);
+            assert_eq!(bytes.to_base64::<8>(Variant::UrlSafeNoPad), unpadded);
+            assert_eq!(encoded_len(decoded.len(), Variant::UrlSafe), encoded.len());
+            assert_eq!(
+                encoded_len(decoded.len(), Variant::StandardNoPad),
+                unpadded.len()
+            );
+            assert_eq!(
+                Bytes::from_base64(encoded, Variant::Standard),
+                Ok(bytes.clone())
+            );
+            assert_eq!(
+                Bytes::from_base64(unpadded, Variant::UrlSafeNoPad),
+                Ok(bytes)
+            );
+            let string = encoded.try_into().unwrap();
+            assert_eq!(
+                Bytes::<8>::from_base64_string(string, Variant::UrlSafe).unwrap(),
+                decoded
+            );
+        }
+        assert_eq!(Bytes::<32>::BASE64_LEN, 44);
+
+        let bytes = Bytes::<4>::from_slice(&[0xfb, 0xff, 0xbf]).unwrap();
+        assert_eq!(bytes.to_base64::<8>(Variant::Standard), "+/+/");
+        assert_eq!(bytes.to_base64::<8>(Variant::UrlSafe), "-_-_");
+        assert_eq!(
+            Bytes::from_base64("-_-_", Variant::UrlSafeNoPad),
+            Ok(bytes.clone())
+        );
+        assert_eq!(
+            Bytes::<4>::from_base64("-_-_", Variant::Standard),
+            Err(Base64Error::InvalidSymbol { index: 0 })
+        );
+        assert_eq!(
+            Bytes::<4>::from_base64("+/+/", Variant::UrlSafe),
+            Err(Base64Error::InvalidSymbol { index: 0 })
+        );
+
+        for (invalid, variant, error) in [
+            ("Zg", Variant::Standard, Base64Error::InvalidLength),
+            ("Zg=", Variant::Standard, Base64Error::InvalidLength),
+            ("Z===", Variant::Standard, Base64Error::InvalidPadding),
+            ("Zm=v", Variant::Standard, Base64Error::InvalidPadding),
+            ("Zm9v=A==", Variant::Standard, Base64Error::InvalidPadding),
+            ("Zg==", Variant::StandardNoPad, Base64Error::InvalidPadding),
+            ("Zm9vY", Variant::UrlSafeNoPad, Base64Error::InvalidLength),
+            ("Zh==", Variant::Standard, Base64Error::NonCanonical),
+            ("Zh", Variant::UrlSafeNoPad, Base64Error::NonCanonical),
+            (
+                "Zm9-",
+                Variant::Standard,
+                Base64Error::InvalidSymbol { index: 3 },
+            ),
+            (
+                "Zm9v Zg",
+                Variant::StandardNoPad,
+                Base64Error::InvalidSymbol { index: 4 },
+            ),
+        ] {
+            assert_eq!(
+                Bytes::<6>::from_base64(invalid, variant),
+                Err(error),
+                "{}",
+                invalid
+            );
+        }
+        assert_eq!(
+            Bytes::<5>::from_base64("Zm9vYmFy", Variant::Standard),
+            Err(Base64Error::Capacity(CapacityError {
+                capacity: 5,
+                requested: 6
+            }))
+        );
+        assert_eq!(
+            Bytes::<5>::from_base64("Zm9vYmE", Variant::UrlSafeNoPad),
+            Ok(Bytes::from_slice(b"fooba").unwrap())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cbor")]
     fn test_client_data_hash() {
         let mut minimal = [
             0x50u8, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x30, 0x41, 0x42, 0x43,
@@ -443,4 +901,1241 @@ mod tests {
 
         assert_eq!(client_data_hash, b"1234567890ABCDEF");
     }
+
+    #[cfg(any(
+        feature = "cbor",
+        feature = "cbor-minicbor",
+        feature = "cbor-smol",
+        feature = "postcard"
+    ))]
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Request<'a> {
+        id: u32,
+        #[serde(borrow)]
+        rp_id: &'a str,
+        hash: Bytes<8>,
+    }
+
+    /// COSE-style map with integer keys, as used by CTAP.
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    struct CoseKey<'a>(&'a Bytes<4>);
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    impl serde::Serialize for CoseKey<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeMap;
+            let mut map = serializer.serialize_map(Some(4))?;
+            map.serialize_entry(&1, &2)?;
+            map.serialize_entry(&3, &-7)?;
+            map.serialize_entry(&-1, &1)?;
+            map.serialize_entry(&-2, self.0)?;
+            map.end()
+        }
+    }
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    fn check_encoding<T: serde::Serialize + ?Sized>(
+        backend: &impl cbor::Backend,
+        t: &T,
+        expected: &[u8],
+    ) {
+        let encoded = Bytes::<32>::try_from_serialized_with(t, backend).unwrap();
+        assert_eq!(encoded, expected);
+    }
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    fn check_backend<B: cbor::Backend>(backend: &B) {
+        // the encodings all backends must agree on
+        let client_data_hash = Bytes::<16>::from_slice(b"1234567890ABCDEF").unwrap();
+        check_encoding(backend, &client_data_hash, b"\x501234567890ABCDEF");
+        check_encoding(backend, &0u8, &[0x00]);
+        check_encoding(backend, &23u8, &[0x17]);
+        check_encoding(backend, &24u8, &[0x18, 0x18]);
+        check_encoding(backend, &256u16, &[0x19, 0x01, 0x00]);
+        check_encoding(backend, &65536u32, &[0x1a, 0x00, 0x01, 0x00, 0x00]);
+        check_encoding(
+            backend,
+            &u64::MAX,
+            &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        );
+        check_encoding(backend, &-1i8, &[0x20]);
+        check_encoding(backend, &-25i32, &[0x38, 0x18]);
+        check_encoding(backend, &-257i64, &[0x39, 0x01, 0x00]);
+        check_encoding(backend, "rp", &[0x62, b'r', b'p']);
+        check_encoding(backend, &(1u8, 2u8, 3u8), &[0x83, 0x01, 0x02, 0x03]);
+        check_encoding(backend, &true, &[0xf5]);
+        check_encoding(backend, &None::<u8>, &[0xf6]);
+        check_encoding(backend, &Some(1u8), &[0x01]);
+        check_encoding(backend, &(), &[0xf6]);
+        let x = Bytes::<4>::from_slice(b"xxxx").unwrap();
+        check_encoding(
+            backend,
+            &CoseKey(&x),
+            &[
+                0xa4, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01, 0x21, 0x44, b'x', b'x', b'x', b'x',
+            ],
+        );
+
+        let request = Request {
+            id: 7,
+            rp_id: "example.com",
+            hash: Bytes::from_slice(b"12345678").unwrap(),
+        };
+        let encoded = Bytes::<64>::try_from_serialized_with(&request, backend).unwrap();
+        let decoded: Request<'_> = encoded.to_deserialized_with(backend).unwrap();
+        assert_eq!(decoded, request);
+        assert_eq!(
+            encoded.to_deserialized_with::<u32, _>(backend),
+            Err(CodecError::Deserialize)
+        );
+
+        // 0x50 header followed by the 16 bytes
+        assert_eq!(
+            Bytes::<8>::try_from_serialized_with(&client_data_hash, backend),
+            Err(CodecError::Capacity(CapacityError {
+                capacity: 8,
+                requested: 17
+            }))
+        );
+    }
+
+    /// The map from RFC 8949, section 4.2.1, with its keys shuffled.
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    struct ShuffledKeys;
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    impl serde::Serialize for ShuffledKeys {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeMap;
+            let mut map = serializer.serialize_map(Some(8))?;
+            map.serialize_entry(&false, &0)?;
+            map.serialize_entry(&[-1], &0)?;
+            map.serialize_entry("aa", &0)?;
+            map.serialize_entry(&100, &0)?;
+            map.serialize_entry(&[100], &0)?;
+            map.serialize_entry("z", &0)?;
+            map.serialize_entry(&-1, &0)?;
+            map.serialize_entry(&10, &0)?;
+            map.end()
+        }
+    }
+
+    /// A sequence of unknown length, encoded with an indefinite length.
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    struct Indefinite<'a>(&'a [u8]);
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    impl serde::Serialize for Indefinite<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.0.iter().filter(|_| true))
+        }
+    }
+
+    /// `[_ 1, [2, 3], [_ 4, 5]]` from RFC 8949, appendix A.
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    struct Nested;
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    impl serde::Serialize for Nested {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeSeq;
+            let mut seq = serializer.serialize_seq(None)?;
+            seq.serialize_element(&1)?;
+            seq.serialize_element(&(2, 3))?;
+            seq.serialize_element(&Indefinite(&[4, 5]))?;
+            seq.end()
+        }
+    }
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    struct DuplicateKeys;
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    impl serde::Serialize for DuplicateKeys {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeMap;
+            let mut map = serializer.serialize_map(Some(2))?;
+            map.serialize_entry(&1, &2)?;
+            map.serialize_entry(&1, &3)?;
+            map.end()
+        }
+    }
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    #[derive(serde::Serialize)]
+    struct Outer {
+        a: u8,
+        inner: Inner,
+    }
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    #[derive(serde::Serialize)]
+    struct Inner {
+        b: u8,
+    }
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    #[derive(serde::Serialize)]
+    enum Variant {
+        Unit,
+        Newtype(u8),
+        Tuple(u8, u8),
+        Struct { a: u8 },
+    }
+
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor", feature = "cbor-smol"))]
+    fn check_options<B: cbor::Backend + Copy>(backend: B) {
+        use cbor::CborOptions;
+
+        let named = CborOptions::with_backend(backend);
+        let packed = named.packed(true);
+        let outer = Outer {
+            a: 1,
+            inner: Inner { b: 2 },
+        };
+        check_encoding(&named, &outer, b"\xa2\x61a\x01\x65inner\xa1\x61b\x02");
+        check_encoding(&packed, &outer, b"\xa2\x00\x01\x01\xa1\x00\x02");
+        check_encoding(
+            &named.pack_starting_with(1),
+            &outer,
+            b"\xa2\x61a\x01\x65inner\xa1\x00\x02",
+        );
+        check_encoding(
+            &named.pack_to_depth(1),
+            &outer,
+            b"\xa2\x00\x01\x01\xa1\x61b\x02",
+        );
+        check_encoding(&named, &Variant::Unit, b"\x64Unit");
+        check_encoding(&packed, &Variant::Unit, b"\x00");
+        check_encoding(&named, &Variant::Newtype(7), b"\xa1\x67Newtype\x07");
+        check_encoding(&packed, &Variant::Newtype(7), b"\xa1\x01\x07");
+        check_encoding(&named, &Variant::Tuple(1, 2), b"\xa1\x65Tuple\x82\x01\x02");
+        check_encoding(&packed, &Variant::Tuple(1, 2), b"\xa1\x02\x82\x01\x02");
+
+        let variant = Variant::Struct { a: 1 };
+        check_encoding(&named, &variant, b"\xa1\x66Struct\xa1\x61a\x01");
+        check_encoding(&packed, &variant, b"\xa1\x03\xa1\x00\x01");
+        check_encoding(
+            &named.pack_starting_with(1),
+            &variant,
+            b"\xa1\x66Struct\xa1\x00\x01",
+        );
+        check_encoding(&named.pack_to_depth(1), &variant, b"\xa1\x03\xa1\x61a\x01");
+
+        // RFC 8949, section 4.2.1
+        let canonical = named.canonical(true);
+        check_encoding(
+            &canonical,
+            &ShuffledKeys,
+            &[
+                0xa8, 0x0a, 0x00, 0x18, 0x64, 0x00, 0x20, 0x00, 0x61, 0x7a, 0x00, 0x62, 0x61, 0x61,
+                0x00, 0x81, 0x18, 0x64, 0x00, 0x81, 0x20, 0x00, 0xf4, 0x00,
+            ],
+        );
+
+        // RFC 8949, appendix A
+        check_encoding(&canonical, &1_000_000u32, &[0x1a, 0x00, 0x0f, 0x42, 0x40]);
+
+        let x = Bytes::<4>::from_slice(b"xxxx").unwrap();
+        check_encoding(
+            &canonical,
+            &CoseKey(&x),
+            &[
+                0xa4, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01, 0x21, 0x44, b'x', b'x', b'x', b'x',
+            ],
+        );
+
+        check_encoding(
+            &canonical,
+            &Nested,
+            &[0x83, 0x01, 0x82, 0x02, 0x03, 0x82, 0x04, 0x05],
+        );
+        assert_eq!(
+            Bytes::<32>::try_from_serialized_with(&DuplicateKeys, &canonical),
+            Err(CodecError::Serialize)
+        );
+    }
+
+    /// Preferred float serialization, from RFC 8949, appendix A.
+    #[cfg(any(feature = "cbor", feature = "cbor-minicbor"))]
+    fn check_canonical_floats<B: cbor::Backend>(backend: B) {
+        let canonical = cbor::CborOptions::with_backend(backend).canonical(true);
+        check_encoding(&canonical, &0.0f64, &[0xf9, 0x00, 0x00]);
+        check_encoding(&canonical, &-0.0f64, &[0xf9, 0x80, 0x00]);
+        check_encoding(&canonical, &1.0f64, &[0xf9, 0x3c, 0x00]);
+        check_encoding(
+            &canonical,
+            &1.1f64,
+            &[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+        );
+        check_encoding(&canonical, &1.5f32, &[0xf9, 0x3e, 0x00]);
+        check_encoding(&canonical, &65504.0f64, &[0xf9, 0x7b, 0xff]);
+        check_encoding(&canonical, &100000.0f64, &[0xfa, 0x47, 0xc3, 0x50, 0x00]);
+        check_encoding(
+            &canonical,
+            &3.4028234663852886e+38f64,
+            &[0xfa, 0x7f, 0x7f, 0xff, 0xff],
+        );
+        check_encoding(
+            &canonical,
+            &1.0e+300f64,
+            &[0xfb, 0x7e, 0x37, 0xe4, 0x3c, 0x88, 0x00, 0x75, 0x9c],
+        );
+        check_encoding(&canonical, &5.960464477539063e-8f64, &[0xf9, 0x00, 0x01]);
+        check_encoding(&canonical, &0.00006103515625f64, &[0xf9, 0x04, 0x00]);
+        check_encoding(&canonical, &-4.0f64, &[0xf9, 0xc4, 0x00]);
+        check_encoding(
+            &canonical,
+            &-4.1f64,
+            &[0xfb, 0xc0, 0x10, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66],
+        );
+        check_encoding(&canonical, &f64::INFINITY, &[0xf9, 0x7c, 0x00]);
+        check_encoding(&canonical, &f32::NAN, &[0xf9, 0x7e, 0x00]);
+        check_encoding(&canonical, &f64::NEG_INFINITY, &[0xf9, 0xfc, 0x00]);
+    }
+
+    #[test]
+    #[cfg(feature = "cbor")]
+    fn test_serde_cbor_backend() {
+        check_backend(&cbor::SerdeCbor);
+        check_options(cbor::SerdeCbor);
+        check_canonical_floats(cbor::SerdeCbor);
+    }
+
+    #[test]
+    #[cfg(feature = "cbor-minicbor")]
+    fn test_minicbor_backend() {
+        check_backend(&cbor::Minicbor);
+        check_options(cbor::Minicbor);
+        check_canonical_floats(cbor::Minicbor);
+    }
+
+    #[test]
+    #[cfg(feature = "cbor-smol")]
+    fn test_cbor_smol_backend() {
+        check_backend(&cbor::CborSmol);
+        check_options(cbor::CborSmol);
+
+        // cbor-smol panics on floats, the backend rejects them
+        let smol = cbor::CborSmol;
+        assert_eq!(
+            Bytes::<16>::try_from_serialized_with(&1.5f32, &smol),
+            Err(CodecError::Serialize)
+        );
+        assert_eq!(
+            Bytes::<16>::try_from_serialized_with(&(1u8, [Some(2.5f64)]), &smol),
+            Err(CodecError::Serialize)
+        );
+        let canonical = cbor::CborOptions::with_backend(smol).canonical(true);
+        assert_eq!(
+            Bytes::<16>::try_from_serialized_with(&Variant::Newtype(1), &canonical).unwrap(),
+            b"\xa1\x67Newtype\x01"
+        );
+        assert_eq!(
+            Bytes::<16>::try_from_serialized_with(&[0.0f64], &canonical),
+            Err(CodecError::Serialize)
+        );
+        assert_eq!(
+            Bytes::<16>::try_from_serialized_with(&format_args!("{}", 1), &smol),
+            Err(CodecError::Serialize)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cbor")]
+    fn test_default_backend() {
+        let encoded = Bytes::<8>::try_from_serialized(&(1u8, "a")).unwrap();
+        assert_eq!(encoded, [0x82, 0x01, 0x61, b'a']);
+        assert_eq!(encoded.to_deserialized(), Ok((1u8, "a")));
+
+        // packed, whichever other backends are enabled
+        let encoded = Bytes::<8>::try_from_serialized(&Inner { b: 2 }).unwrap();
+        assert_eq!(encoded, [0xa1, 0x00, 0x02]);
+    }
+
+    #[test]
+    #[cfg(feature = "postcard")]
+    fn test_postcard() {
+        let request = Request {
+            id: 7,
+            rp_id: "example.com",
+            hash: Bytes::from_slice(&[0; 8]).unwrap(),
+        };
+        let mut expected = [0u8; 22];
+        expected[..2].copy_from_slice(&[0x07, 0x0b]);
+        expected[2..13].copy_from_slice(b"example.com");
+        expected[13] = 0x08;
+
+        let encoded = Bytes::<32>::try_from_postcard(&request).unwrap();
+        assert_eq!(encoded, &expected);
+        assert_eq!(encoded.to_postcard::<Request<'_>>().as_ref(), Ok(&request));
+        assert_eq!(
+            Bytes::<16>::try_from_postcard(&request),
+            Err(CodecError::Capacity(CapacityError {
+                capacity: 16,
+                requested: 22
+            }))
+        );
+
+        let mut trailing = encoded.clone();
+        trailing.push(0).unwrap();
+        assert_eq!(
+            trailing.to_postcard::<Request<'_>>(),
+            Err(CodecError::Deserialize)
+        );
+
+        // the eight zero bytes of the hash each end a COBS block
+        let mut framed = Bytes::<32>::try_from_postcard_cobs(&request).unwrap();
+        assert_eq!(framed.len(), 24);
+        assert_eq!(framed.iter().position(|&byte| byte == 0), Some(23));
+        assert!(matches!(
+            Bytes::<16>::try_from_postcard_cobs(&request),
+            Err(CodecError::Capacity(CapacityError { capacity: 16, .. }))
+        ));
+        assert_eq!(framed.to_postcard_cobs(), Ok(request));
+    }
+
+    #[cfg(feature = "minicbor")]
+    #[derive(Debug, PartialEq, minicbor::Encode, minicbor::Decode, minicbor::CborLen)]
+    struct Credential {
+        #[n(0)]
+        id: Bytes<16>,
+        #[n(1)]
+        key: Option<Bytes<4>>,
+    }
+
+    #[test]
+    #[cfg(feature = "minicbor")]
+    fn test_minicbor_traits() {
+        let credential = Credential {
+            id: Bytes::from_slice(b"1234").unwrap(),
+            key: Some(Bytes::from_slice(b"abcd").unwrap()),
+        };
+        let encoded = minicbor::to_vec(&credential).unwrap();
+        assert_eq!(
+            encoded,
+            [0x82, 0x44, b'1', b'2', b'3', b'4', 0x44, b'a', b'b', b'c', b'd']
+        );
+        assert_eq!(minicbor::len(&credential), encoded.len());
+        assert_eq!(
+            minicbor::decode::<Credential>(&encoded).unwrap(),
+            credential
+        );
+
+        // same encoding as serde
+        #[cfg(feature = "cbor")]
+        assert_eq!(
+            Bytes::<8>::try_from_serialized_with(&credential.key, &cbor::SerdeCbor).unwrap(),
+            encoded[6..]
+        );
+
+        // indefinite length, in chunks
+        let indefinite = [0x5f, 0x42, b'a', b'b', 0x41, b'c', 0xff];
+        assert_eq!(minicbor::decode::<Bytes<3>>(&indefinite).unwrap(), b"abc");
+        assert!(minicbor::decode::<Bytes<2>>(&indefinite).is_err());
+        assert!(minicbor::decode::<Bytes<3>>(&encoded[1..]).is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Status<'a> {
+        id: Bytes<4>,
+        name: &'a str,
+        uptime: u32,
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json() {
+        let status = Status {
+            id: Bytes::from_slice(&[0xde, 0xad, 0xbe, 0xef]).unwrap(),
+            name: "sensor",
+            uptime: 42,
+        };
+        let json = r#"{"id":"deadbeef","name":"sensor","uptime":42}"#;
+
+        let encoded = Bytes::<64>::try_from_json(&status).unwrap();
+        assert_eq!(encoded, json.as_bytes());
+        assert_eq!(encoded.to_json::<Status<'_>>().as_ref(), Ok(&status));
+        assert_eq!(
+            Bytes::<16>::try_from_json(&status),
+            Err(CodecError::Capacity(CapacityError {
+                capacity: 16,
+                requested: 17
+            }))
+        );
+
+        let mut trailing = encoded.clone();
+        trailing.extend_from_slice(b" }").unwrap();
+        assert_eq!(
+            trailing.to_json::<Status<'_>>(),
+            Err(CodecError::Deserialize)
+        );
+        let too_long =
+            Bytes::<64>::from_slice(br#"{"id":"0011223344","name":"","uptime":0}"#).unwrap();
+        assert_eq!(
+            too_long.to_json::<Status<'_>>(),
+            Err(CodecError::Deserialize)
+        );
+        for id in [r#""0011223""#, r#""001122gg""#, "[0,1,2,3]"] {
+            let json = format!(r#"{{"id":{},"name":"","uptime":0}}"#, id);
+            let invalid = Bytes::<64>::from_slice(json.as_bytes()).unwrap();
+            assert_eq!(
+                invalid.to_json::<Status<'_>>(),
+                Err(CodecError::Deserialize)
+            );
+        }
+
+        // hex digits cannot end the string early
+        let injected = Status {
+            id: Bytes::from_slice(b"a\"}x").unwrap(),
+            name: "",
+            uptime: 0,
+        };
+        let encoded = Bytes::<64>::try_from_json(&injected).unwrap();
+        assert_eq!(encoded, br#"{"id":"61227d78","name":"","uptime":0}"#);
+        assert_eq!(encoded.to_json::<Status<'_>>(), Ok(injected));
+
+        let id = HexBytes(Bytes::<4>::from_slice(b"a\"}x").unwrap());
+        let encoded = Bytes::<16>::try_from_json(&id).unwrap();
+        assert_eq!(encoded, br#""61227d78""#);
+        assert_eq!(encoded.to_json(), Ok(id));
+
+        #[cfg(feature = "base64")]
+        {
+            let key = Base64Bytes(Bytes::<5>::from_slice(b"hello").unwrap());
+            let encoded = Bytes::<16>::try_from_json(&key).unwrap();
+            assert_eq!(encoded, br#""aGVsbG8=""#);
+            assert_eq!(encoded.to_json(), Ok(key));
+        }
+    }
+
+    #[cfg(feature = "bincode")]
+    #[derive(Debug, PartialEq)]
+    struct Record {
+        serial: u32,
+        key: Bytes<8>,
+    }
+
+    // written out, as deriving would need bincode as a dev-dependency, which
+    // needs Rust 1.85 while the MSRV is 1.81
+    #[cfg(feature = "bincode")]
+    impl ::bincode::Encode for Record {
+        fn encode<E: ::bincode::enc::Encoder>(
+            &self,
+            encoder: &mut E,
+        ) -> Result<(), ::bincode::error::EncodeError> {
+            self.serial.encode(encoder)?;
+            self.key.encode(encoder)
+        }
+    }
+
+    #[cfg(feature = "bincode")]
+    impl<Context> ::bincode::Decode<Context> for Record {
+        fn decode<D: ::bincode::de::Decoder<Context = Context>>(
+            decoder: &mut D,
+        ) -> Result<Self, ::bincode::error::DecodeError> {
+            Ok(Record {
+                serial: ::bincode::Decode::decode(decoder)?,
+                key: ::bincode::Decode::decode(decoder)?,
+            })
+        }
+    }
+
+    #[cfg(feature = "bincode")]
+    ::bincode::impl_borrow_decode!(Record);
+
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn test_bincode() {
+        let record = Record {
+            serial: 7,
+            key: Bytes::from_slice(b"abc").unwrap(),
+        };
+        let encoded = Bytes::<16>::try_from_bincode(&record).unwrap();
+        assert_eq!(encoded, [7, 3, b'a', b'b', b'c']);
+        assert_eq!(encoded.to_bincode(), Ok(record));
+
+        // same wire format as `Vec<u8>`
+        let mut vec = [0u8; 8];
+        let size =
+            ::bincode::encode_into_slice(&b"abc"[..], &mut vec, ::bincode::config::standard())
+                .unwrap();
+        assert_eq!(encoded[1..], vec[..size]);
+
+        assert_eq!(
+            Bytes::<4>::try_from_bincode(&Bytes::<8>::from_slice(b"abcd").unwrap()),
+            Err(CodecError::Capacity(CapacityError {
+                capacity: 4,
+                requested: 5
+            }))
+        );
+        let key = Bytes::<8>::from_slice(&[3, b'a', b'b', b'c']).unwrap();
+        assert_eq!(key.to_bincode::<Bytes<4>>().unwrap(), b"abc");
+        assert_eq!(key.to_bincode::<Bytes<2>>(), Err(CodecError::Deserialize));
+        assert_eq!(
+            encoded.to_bincode::<Bytes<8>>(),
+            Err(CodecError::Deserialize)
+        );
+    }
+
+    #[cfg(feature = "der")]
+    #[derive(Debug, PartialEq, ::der::Sequence)]
+    struct PublicKey {
+        exponent: crate::der::UintBytes<4>,
+        modulus: crate::der::BitStringBytes<8>,
+        id: Bytes<8>,
+    }
+
+    #[test]
+    #[cfg(feature = "der")]
+    fn test_der() {
+        use crate::der::{BitStringBytes, UintBytes};
+
+        let id = Bytes::<8>::from_slice(b"abc").unwrap();
+        let encoded = Bytes::<16>::try_from_der(&id).unwrap();
+        assert_eq!(encoded, [0x04, 3, b'a', b'b', b'c']);
+        assert_eq!(encoded.to_der(), Ok(id.clone()));
+        assert_eq!(encoded.to_der::<Bytes<2>>(), Err(CodecError::Deserialize));
+        assert_eq!(
+            Bytes::<4>::try_from_der(&id),
+            Err(CodecError::Capacity(CapacityError {
+                capacity: 4,
+                requested: 5
+            }))
+        );
+
+        let bits = BitStringBytes(Bytes::<8>::from_slice(&[0xff, 0x01]).unwrap());
+        let encoded = Bytes::<16>::try_from_der(&bits).unwrap();
+        assert_eq!(encoded, [0x03, 3, 0, 0xff, 0x01]);
+        assert_eq!(encoded.to_der(), Ok(bits));
+        // 1 unused bit
+        let bits = Bytes::<8>::from_slice(&[0x03, 2, 1, 0xfe]).unwrap();
+        assert_eq!(
+            bits.to_der::<BitStringBytes<8>>(),
+            Err(CodecError::Deserialize)
+        );
+
+        // a leading zero keeps the INTEGER positive
+        let uint = UintBytes(Bytes::<4>::from_slice(&[0x80, 0x01]).unwrap());
+        let encoded = Bytes::<16>::try_from_der(&uint).unwrap();
+        assert_eq!(encoded, [0x02, 3, 0, 0x80, 0x01]);
+        assert_eq!(encoded.to_der(), Ok(uint));
+        let uint = UintBytes(Bytes::<4>::from_slice(&[0, 0, 0x01]).unwrap());
+        let encoded = Bytes::<16>::try_from_der(&uint).unwrap();
+        assert_eq!(encoded, [0x02, 1, 0x01]);
+        assert_eq!(encoded.to_der::<UintBytes<1>>().unwrap().0, [0x01]);
+
+        let key = PublicKey {
+            exponent: UintBytes(Bytes::from_slice(&[0x01, 0x00, 0x01]).unwrap()),
+            modulus: BitStringBytes(Bytes::from_slice(&[0xc0, 0xff, 0xee]).unwrap()),
+            id,
+        };
+        let encoded = Bytes::<32>::try_from_der(&key).unwrap();
+        assert_eq!(
+            encoded,
+            [
+                0x30, 16, 0x02, 3, 0x01, 0x00, 0x01, 0x03, 4, 0, 0xc0, 0xff, 0xee, 0x04, 3, b'a',
+                b'b', b'c'
+            ]
+        );
+        assert_eq!(encoded.to_der(), Ok(key));
+        assert_eq!(
+            Bytes::<8>::try_from_der(&encoded.to_der::<PublicKey>().unwrap()),
+            Err(CodecError::Capacity(CapacityError {
+                capacity: 8,
+                requested: 18
+            }))
+        );
+    }
+
+    /// The bytes past the length, which must all have been written before.
+    #[cfg(feature = "zeroize")]
+    fn spare<const N: usize>(bytes: &Bytes<N>) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(bytes.as_ptr().add(bytes.len()), N - bytes.len()) }
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn test_zeroize() {
+        use ::zeroize::Zeroize;
+        use core::mem::ManuallyDrop;
+
+        let mut bytes = Bytes::<8>::from_slice(b"secret!!").unwrap();
+        unsafe { bytes.set_len(3) };
+        assert_eq!(spare(&bytes), b"ret!!");
+        bytes.zeroize();
+        assert!(bytes.is_empty());
+        assert_eq!(spare(&bytes), [0; 8]);
+
+        let mut bytes = ManuallyDrop::new(Bytes::<8>::from_slice(b"secret!!").unwrap());
+        unsafe { ManuallyDrop::drop(&mut bytes) };
+        assert_eq!(spare(&bytes), [0; 8]);
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize-on-shrink")]
+    fn test_zeroize_on_shrink() {
+        let mut bytes = Bytes::<8>::from_slice(b"abcdefgh").unwrap();
+        bytes.truncate(5);
+        assert_eq!(bytes, b"abcde");
+        assert_eq!(spare(&bytes), [0; 3]);
+        assert_eq!(bytes.pop(), Some(b'e'));
+        assert_eq!(spare(&bytes), [0; 4]);
+        assert_eq!(bytes.remove(0), Ok(b'a'));
+        assert_eq!(bytes, b"bcd");
+        assert_eq!(spare(&bytes), [0; 5]);
+        bytes.resize_default(1).unwrap();
+        assert_eq!(spare(&bytes), [0; 7]);
+        bytes.clear();
+        assert_eq!(spare(&bytes), [0; 8]);
+        assert_eq!(bytes.pop(), None);
+
+        let mut bytes = Bytes::<8>::from_slice(b"abcdefgh").unwrap();
+        bytes.resize(6, b'x').unwrap();
+        assert_eq!(spare(&bytes), [0; 2]);
+        bytes.resize(7, b'x').unwrap();
+        assert_eq!(bytes, b"abcdefx");
+        assert_eq!(bytes.swap_remove(1), Ok(b'b'));
+        assert_eq!(bytes, b"axcdef");
+        assert_eq!(spare(&bytes), [0; 2]);
+        assert!(bytes.swap_remove(6).is_err());
+        bytes.retain(|&byte| byte != b'c');
+        assert_eq!(bytes, b"axdef");
+        assert_eq!(spare(&bytes), [0; 3]);
+        bytes.retain_mut(|byte| {
+            *byte = byte.to_ascii_uppercase();
+            *byte != b'X'
+        });
+        assert_eq!(bytes, b"ADEF");
+        assert_eq!(spare(&bytes), [0; 4]);
+
+        let bytes = Bytes::<8>::try_from(|buf| {
+            buf.fill(0xff);
+            Ok::<_, ()>(2)
+        })
+        .unwrap();
+        assert_eq!(bytes, [0xff, 0xff]);
+        assert_eq!(spare(&bytes), [0; 6]);
+    }
+
+    #[test]
+    #[cfg(feature = "subtle")]
+    fn test_subtle() {
+        use ::subtle::{Choice, ConstantTimeEq};
+
+        let mac = Bytes::<8>::from_slice(b"abcd").unwrap();
+        assert!(bool::from(mac.ct_eq(b"abcd")));
+        assert!(!bool::from(mac.ct_eq(b"abce")));
+        assert!(!bool::from(mac.ct_eq(b"abc")));
+        assert!(!bool::from(mac.ct_eq(b"abcd\0")));
+        assert!(!bool::from(mac.ct_eq(b"abcd\0\0\0\0\0\0")));
+        assert!(bool::from(Bytes::<0>::new().ct_eq(b"")));
+        assert!(bool::from(ConstantTimeEq::ct_eq(&mac, &mac.clone())));
+        assert!(!bool::from(ConstantTimeEq::ct_eq(&mac, &Bytes::new())));
+
+        let ct = CtBytes(mac.clone());
+        assert_eq!(ct, b"abcd");
+        assert_ne!(ct, b"abc");
+        assert_eq!(ct, CtBytes::from(mac.clone()));
+        assert!(bool::from(ct.ct_eq(&CtBytes(mac.clone()))));
+
+        let pin = Bytes::<8>::from_slice(b"123456").unwrap();
+        assert_eq!(
+            Bytes::conditional_select(&mac, &pin, Choice::from(0)),
+            b"abcd"
+        );
+        assert_eq!(
+            Bytes::conditional_select(&mac, &pin, Choice::from(1)),
+            b"123456"
+        );
+        let mut a = mac.clone();
+        a.conditional_assign(&pin, Choice::from(0));
+        assert_eq!(a, b"abcd");
+        a.conditional_assign(&pin, Choice::from(1));
+        assert_eq!(a, b"123456");
+
+        let (mut a, mut b) = (mac.clone(), pin.clone());
+        Bytes::conditional_swap(&mut a, &mut b, Choice::from(0));
+        assert_eq!((&a, &b), (&mac, &pin));
+        Bytes::conditional_swap(&mut a, &mut b, Choice::from(1));
+        assert_eq!((&a, &b), (&pin, &mac));
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn test_secret_bytes() {
+        use serde::de::value::{BorrowedBytesDeserializer, Error as ValueError};
+
+        let mut secret = SecretBytes::<32>::new(Bytes::from_slice(b"correct horse bat").unwrap());
+        assert_eq!(format!("{:?}", secret), "SecretBytes<32>([REDACTED; 17])");
+        assert_eq!(secret.expose_secret(), b"correct horse bat");
+        secret.expose_secret_mut().truncate(7);
+        assert_eq!(secret.expose_secret(), b"correct");
+
+        let bytes = BorrowedBytesDeserializer::<ValueError>::new(b"1234");
+        let pin = SecretBytes::<8>::deserialize(bytes).unwrap();
+        assert_eq!(pin.expose_secret(), b"1234");
+        assert_eq!(format!("{:?}", pin), "SecretBytes<8>([REDACTED; 4])");
+    }
+
+    #[test]
+    #[cfg(feature = "serialize-secrets")]
+    fn test_serialize_secrets() {
+        let bytes = Bytes::<8>::from_slice(b"1234").unwrap();
+        assert_eq!(
+            serde_json::to_string(&SecretBytes::from(bytes.clone())).unwrap(),
+            serde_json::to_string(&bytes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hex() {
+        let bytes = Bytes::<4>::from_hex("00c0FFee").unwrap();
+        assert_eq!(bytes, [0x00, 0xc0, 0xff, 0xee]);
+        assert_eq!(bytes.to_hex::<8>(), "00c0ffee");
+        assert_eq!(format!("{}", bytes.hex()), "00c0ffee");
+        assert_eq!(format!("{:x}", bytes), "00c0ffee");
+        assert_eq!(format!("{:#x}", bytes), "0x00c0ffee");
+        assert_eq!(format!("{:X}", bytes), "00C0FFEE");
+        assert_eq!(format!("{:#X}", bytes), "0x00C0FFEE");
+        assert_eq!(format!("{:#x}", Bytes::<4>::new()), "0x");
+
+        let short = Bytes::<4>::from_slice(&[0xbe, 0xef]).unwrap();
+        assert_eq!(format!("{:08x}", short), "0000beef");
+        assert_eq!(format!("{:#08X}", short), "0x00BEEF");
+        assert_eq!(format!("{:6x}", short), "  beef");
+        assert_eq!(format!("{:*<7x}", short), "beef***");
+        assert_eq!(format!("{:^#9x}", short), " 0xbeef  ");
+        assert_eq!(format!("{:2x}", short), "beef");
+
+        let long = Bytes::<64>::from_slice(&[0xab; 64]).unwrap();
+        assert_eq!(long.to_hex::<128>(), "ab".repeat(64).as_str());
+
+        assert_eq!(Bytes::<4>::from_hex(""), Ok(Bytes::new()));
+        assert_eq!(Bytes::<4>::from_hex("abc"), Err(HexError::OddLength));
+        assert_eq!(
+            Bytes::<4>::from_hex("0x00"),
+            Err(HexError::InvalidDigit { index: 1 })
+        );
+        assert_eq!(
+            Bytes::<2>::from_hex("c0ffee"),
+            Err(HexError::Capacity(CapacityError {
+                capacity: 2,
+                requested: 3
+            }))
+        );
+        assert_eq!(
+            HexError::InvalidDigit { index: 1 }.to_string(),
+            "invalid hex digit at index 1"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "base32")]
+    fn test_base32() {
+        use base32::{encoded_len, Variant};
+
+        // RFC 4648, section 10
+        for (decoded, standard, hex) in [
+            (&b""[..], "", ""),
+            (b"f", "MY======", "CO======"),
+            (b"fo", "MZXQ====", "CPNG===="),
+            (b"foo", "MZXW6===", "CPNMU==="),
+            (b"foob", "MZXW6YQ=", "CPNMUOG="),
+            (b"fooba", "MZXW6YTB", "CPNMUOJ1"),
+            (b"foobar", "MZXW6YTBOI======", "CPNMUOJ1E8======"),
+        ] {
+            let bytes = Bytes::<6>::from_slice(decoded).unwrap();
+            let unpadded = standard.trim_end_matches('=');
+            assert_eq!(bytes.base32(Variant::Standard).to_string(), standard);
+            assert_eq!(bytes.to_base32::<16>(Variant::Standard), standard);
+            assert_eq!(bytes.to_base32::<16>(Variant::Hex), hex);
+            assert_eq!(bytes.to_base32::<16>(Variant::StandardNoPad), unpadded);
+            assert_eq!(encoded_len(decoded.len(), Variant::Hex), hex.len());
+            assert_eq!(
+                encoded_len(decoded.len(), Variant::StandardNoPad),
+                unpadded.len()
+            );
+            assert_eq!(
+                Bytes::from_base32(standard, Variant::Standard),
+                Ok(bytes.clone())
+            );
+            assert_eq!(Bytes::from_base32(hex, Variant::Hex), Ok(bytes.clone()));
+            assert_eq!(
+                Bytes::from_base32(unpadded, Variant::StandardNoPad),
+                Ok(bytes)
+            );
+        }
+        assert_eq!(Bytes::<20>::BASE32_LEN, 32);
+
+        // the SHA-1 secret of the RFC 6238 TOTP test vectors
+        let secret = Bytes::<20>::from_slice(b"12345678901234567890").unwrap();
+        let encoded = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+        assert_eq!(secret.to_base32::<32>(Variant::StandardNoPad), encoded);
+        assert_eq!(
+            Bytes::from_base32(encoded, Variant::StandardNoPad),
+            Ok(secret)
+        );
+
+        for (invalid, variant, error) in [
+            ("MY=====", Variant::Standard, Base32Error::InvalidLength),
+            ("MZX=====", Variant::Standard, Base32Error::InvalidLength),
+            ("MZX", Variant::StandardNoPad, Base32Error::InvalidLength),
+            ("M=======", Variant::Standard, Base32Error::InvalidPadding),
+            ("========", Variant::Standard, Base32Error::InvalidPadding),
+            (
+                "MY======",
+                Variant::StandardNoPad,
+                Base32Error::InvalidPadding,
+            ),
+            ("MZ======", Variant::Standard, Base32Error::NonCanonical),
+            ("MZ", Variant::StandardNoPad, Base32Error::NonCanonical),
+            (
+                "my======",
+                Variant::Standard,
+                Base32Error::InvalidSymbol { index: 0 },
+            ),
+            ("MY1", Variant::StandardNoPad, Base32Error::InvalidLength),
+            (
+                "MZXW6YT1",
+                Variant::Standard,
+                Base32Error::InvalidSymbol { index: 7 },
+            ),
+            (
+                "CPNMUOJW",
+                Variant::Hex,
+                Base32Error::InvalidSymbol { index: 7 },
+            ),
+        ] {
+            assert_eq!(
+                Bytes::<6>::from_base32(invalid, variant),
+                Err(error),
+                "{}",
+                invalid
+            );
+        }
+        assert_eq!(
+            Bytes::<5>::from_base32("MZXW6YTBOI", Variant::StandardNoPad),
+            Err(Base32Error::Capacity(CapacityError {
+                capacity: 5,
+                requested: 6
+            }))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "base58")]
+    fn test_base58() {
+        // from Bitcoin Core's base58_encode_decode.json
+        for (hex, encoded) in [
+            ("", ""),
+            ("61", "2g"),
+            ("626262", "a3gV"),
+            ("636363", "aPEr"),
+            (
+                "73696d706c792061206c6f6e6720737472696e67",
+                "2cFupjhnEsSn59qHXstmK2ffpLv2",
+            ),
+            (
+                "00eb15231dfceb60925886b67d065299925915aeb172c06647",
+                "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L",
+            ),
+            ("516b6fcd0f", "ABnLTmg"),
+            ("bf4f89001e670274dd", "3SEo3LWLoPntC"),
+            ("572e4794", "3EFU7m"),
+            ("ecac89cad93923c02321", "EJDM8drfXA6uyA"),
+            ("10c8511e", "Rt5zm"),
+            ("00000000000000000000", "1111111111"),
+        ] {
+            let bytes = Bytes::<25>::from_hex(hex).unwrap();
+            assert_eq!(bytes.to_base58::<35>(), encoded);
+            assert_eq!(Bytes::from_base58(encoded), Ok(bytes));
+        }
+        assert_eq!(Bytes::<25>::BASE58_LEN, 35);
+
+        for (invalid, index) in [("0", 0), ("1O", 1), ("1I", 1), ("abcl", 3), ("2g ", 2)] {
+            assert_eq!(
+                Bytes::<8>::from_base58(invalid),
+                Err(Base58Error::InvalidSymbol { index })
+            );
+        }
+        assert_eq!(
+            Bytes::<2>::from_base58("a3gV"),
+            Err(Base58Error::Capacity(CapacityError {
+                capacity: 2,
+                requested: 3
+            }))
+        );
+        assert_eq!(
+            Bytes::<2>::from_base58("111"),
+            Err(Base58Error::Capacity(CapacityError {
+                capacity: 2,
+                requested: 3
+            }))
+        );
+        // only a lower bound once the number itself overflows
+        assert_eq!(
+            Bytes::<2>::from_base58("1a3gV"),
+            Err(Base58Error::Capacity(CapacityError {
+                capacity: 2,
+                requested: 3
+            }))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "base58check")]
+    fn test_base58check() {
+        // the address and private key examples of the Bitcoin wiki
+        for (hex, encoded) in [
+            (
+                "00010966776006953d5567439e5e39f86a0d273bee",
+                "16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM",
+            ),
+            (
+                "800c28fca386c7a227600b2fe50b7cae11ec86d3bf1fbe471be89827e19d72aa1d",
+                "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ",
+            ),
+            ("", "3QJmnh"),
+            ("00", "1Wh4bh"),
+            ("0000", "112edB6q"),
+            // checksum 009a09c4
+            ("00f8", "1UyqibHu"),
+        ] {
+            let bytes = Bytes::<33>::from_hex(hex).unwrap();
+            assert_eq!(bytes.to_base58check::<52>(), encoded);
+            assert_eq!(Bytes::from_base58check(encoded), Ok(bytes));
+        }
+        assert_eq!(Bytes::<33>::BASE58CHECK_LEN, 52);
+
+        // 193 zero bytes, whose checksum 000bb53e also starts with a zero
+        let zeros = Bytes::<193>::from_slice(&[0; 193]).unwrap();
+        let encoded = zeros.to_base58check::<272>();
+        assert_eq!(encoded.len(), 198);
+        assert!(encoded.starts_with(&"1".repeat(194)));
+        assert!(encoded.ends_with("4w6D"));
+        assert_eq!(Bytes::from_base58check(&encoded), Ok(zeros));
+
+        assert_eq!(
+            Bytes::<33>::from_base58check("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvN"),
+            Err(Base58Error::Checksum)
+        );
+        assert_eq!(
+            Bytes::<33>::from_base58check("111"),
+            Err(Base58Error::InvalidLength)
+        );
+        assert_eq!(
+            Bytes::<33>::from_base58check(""),
+            Err(Base58Error::InvalidLength)
+        );
+        assert_eq!(
+            Bytes::<20>::from_base58check("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM"),
+            Err(Base58Error::Capacity(CapacityError {
+                capacity: 20,
+                requested: 21
+            }))
+        );
+    }
+
+    #[test]
+    fn test_fmt_write() {
+        use core::fmt::Write;
+
+        let mut bytes = Bytes::<16>::new();
+        write!(bytes, "AT+CSQ={}\r\n", 42).unwrap();
+        assert_eq!(bytes, b"AT+CSQ=42\r\n");
+
+        // the first argument fits, the second does not
+        assert_eq!(write!(bytes, "{}{}", "abc", "defg"), Err(core::fmt::Error));
+        assert_eq!(bytes, b"AT+CSQ=42\r\n");
+        assert_eq!(bytes.write_str("abcdef"), Err(core::fmt::Error));
+        assert_eq!(bytes, b"AT+CSQ=42\r\n");
+        bytes.write_str("abcde").unwrap();
+        assert_eq!(bytes, b"AT+CSQ=42\r\nabcde");
+
+        assert_eq!(
+            format_bytes!(16; "AT+CSQ={}\r\n", 42).unwrap(),
+            b"AT+CSQ=42\r\n"
+        );
+        assert_eq!(format_bytes!(0; ""), Ok(Bytes::new()));
+        assert_eq!(
+            format_bytes!(8; "{:>10}", 7),
+            Err(CapacityError {
+                capacity: 8,
+                requested: 10
+            })
+        );
+
+        // arguments are formatted once, even when they do not fit
+        struct Counted<'a>(&'a core::cell::Cell<usize>);
+
+        impl core::fmt::Display for Counted<'_> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.0.set(self.0.get() + 1);
+                f.write_str("0123456789")
+            }
+        }
+
+        let calls = core::cell::Cell::new(0);
+        assert_eq!(
+            format_bytes!(4; "{}{}", Counted(&calls), Counted(&calls)),
+            Err(CapacityError {
+                capacity: 4,
+                requested: 20
+            })
+        );
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "ufmt")]
+    fn test_ufmt() {
+        use ufmt::uwrite;
+
+        let mut bytes = Bytes::<16>::new();
+        uwrite!(bytes, "AT+CSQ={}\r\n", 42u8).unwrap();
+        assert_eq!(bytes, b"AT+CSQ=42\r\n");
+        assert_eq!(
+            uwrite!(bytes, "{}", "abcdef"),
+            Err(CapacityError {
+                capacity: 16,
+                requested: 17
+            })
+        );
+        assert_eq!(bytes, b"AT+CSQ=42\r\n");
+
+        let key = Bytes::<40>::from_slice(&[0xc0; 40]).unwrap();
+        let mut dump = Bytes::<96>::new();
+        uwrite!(
+            dump,
+            "{:?} {}",
+            Bytes::<4>::from_slice(b"\x00\xff").unwrap(),
+            key.hex()
+        )
+        .unwrap();
+        assert_eq!(dump, format!("00ff {}", "c0".repeat(40)).as_bytes());
+    }
+
+    #[test]
+    fn test_literal() {
+        static EMPTY: Bytes<8> = Bytes::new();
+        assert!(EMPTY.is_empty());
+
+        let at = crate::bytes!(16; b"AT+CSQ\r\n");
+        assert_eq!(at, b"AT+CSQ\r\n");
+        assert_eq!(at.capacity(), 16);
+        assert_eq!(crate::bytes!(0; b""), Bytes::<0>::new());
+
+        assert_eq!(crate::hex_bytes!(4; "deadBEEF"), [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(crate::hex_bytes!(8; "00ff"), [0x00, 0xff]);
+        assert_eq!(crate::hex_bytes!(2; ""), Bytes::<2>::new());
+
+        assert_eq!(Bytes::<4>::from_array([1, 2, 3]), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_widen() {
+        let bytes = Bytes::<4>::from_slice(b"1234").unwrap();
+        let wide: Bytes<16> = bytes.widen();
+        assert_eq!(wide, b"1234");
+        assert_eq!(wide.capacity(), 16);
+        assert_eq!(bytes.widen::<4>(), bytes);
+        assert_eq!(Bytes::<0>::new().widen::<0>(), Bytes::<0>::new());
+    }
+
+    #[test]
+    fn test_concat() {
+        let header = Bytes::<4>::from_slice(b"\x01\x02").unwrap();
+        let payload = Bytes::<32>::from_slice(b"payload").unwrap();
+        let tag = Bytes::<16>::from_slice(&[0xff; 16]).unwrap();
+
+        let message: Bytes<52> = header.concat::<_, 36>(&payload).concat(&tag);
+        assert_eq!(&message[..9], b"\x01\x02payload");
+        assert_eq!(&message[9..], [0xff; 16]);
+        assert_eq!(message.capacity(), 52);
+
+        let empty: Bytes<4> = Bytes::<0>::new().concat(&header);
+        assert_eq!(empty, header);
+    }
+
+    #[test]
+    fn test_view() {
+        fn frame(view: &mut BytesView) -> Result<(), Error> {
+            view.insert_slice_at(&[0x02], 0)?;
+            view.extend_from_slice(b"tag")?;
+            view.push(0x03)?;
+            Ok(())
+        }
+
+        let mut small = Bytes::<9>::from_slice(b"data").unwrap();
+        let mut large = Bytes::<64>::from_slice(b"data").unwrap();
+        frame(small.as_mut_view()).unwrap();
+        frame(large.as_mut_view()).unwrap();
+        assert_eq!(small, b"\x02datatag\x03");
+        assert_eq!(large, small);
+        assert_eq!(large.as_view().capacity(), 64);
+        assert_eq!(
+            frame(small.as_mut_view()),
+            Err(Error::Capacity(CapacityError {
+                capacity: 9,
+                requested: 10
+            }))
+        );
+
+        let view = small.as_mut_view();
+        assert_eq!(
+            view.extend_from_slice(b"x"),
+            Err(CapacityError {
+                capacity: 9,
+                requested: 10
+            })
+        );
+        assert_eq!(view.remove(0), Ok(0x02));
+        assert_eq!(view.remove(8), Err(IndexError { index: 8, len: 8 }));
+        view.truncate(4);
+        assert_eq!(view, b"data");
+        assert_eq!(format!("{:?}", view), "b'data'");
+        view.clear();
+        assert!(view.is_empty());
+    }
+
+    #[test]
+    fn test_borrow() {
+        use std::collections::{hash_map::DefaultHasher, BTreeMap};
+
+        fn hash<T: Hash + ?Sized>(t: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            t.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let id = Bytes::<16>::from_slice(b"credential").unwrap();
+        assert_eq!(hash(&id), hash(&b"credential"[..]));
+
+        let mut index = heapless::FnvIndexMap::<Bytes<16>, u8, 4>::new();
+        index.insert(id.clone(), 1).unwrap();
+        assert_eq!(index.get(&b"credential"[..]), Some(&1));
+        assert_eq!(index.get(&b"other"[..]), None);
+
+        let mut linear = heapless::LinearMap::<Bytes<16>, u8, 4>::new();
+        linear.insert(id.clone(), 2).unwrap();
+        assert_eq!(linear.get(&b"credential"[..]), Some(&2));
+
+        let mut sorted = BTreeMap::new();
+        sorted.insert(Bytes::<4>::from_slice(b"b").unwrap(), 2);
+        sorted.insert(Bytes::<4>::from_slice(b"ab").unwrap(), 1);
+        sorted.insert(Bytes::<4>::from_slice(b"").unwrap(), 0);
+        assert_eq!(sorted.get(&b"ab"[..]), Some(&1));
+        assert_eq!(
+            sorted.into_values().collect::<std::vec::Vec<_>>(),
+            [0, 1, 2]
+        );
+
+        let mut heap = heapless::BinaryHeap::<Bytes<4>, heapless::binary_heap::Max, 4>::new();
+        heap.push(Bytes::from_slice(b"a").unwrap()).unwrap();
+        heap.push(Bytes::from_slice(b"ba").unwrap()).unwrap();
+        heap.push(Bytes::from_slice(b"b").unwrap()).unwrap();
+        assert_eq!(heap.pop().unwrap(), b"ba");
+    }
 }
@@ -0,0 +1,64 @@
+//! Byte string literals whose length is checked at compile time.
+//!
+//! The check runs in `const` items, so `cargo check` already reports a
+//! literal that does not fit. The values themselves are built at runtime,
+//! without a capacity check to fail: heapless 0.8 has no `const` constructor
+//! for a non-empty `Vec`, so these macros cannot initialize a `const` or
+//! `static` item. Only the empty [`Bytes::new`] can.
+
+use crate::Bytes;
+
+impl<const N: usize> Bytes<N> {
+    /// Copy `array` into a new `Bytes<N>`.
+    ///
+    /// `M` must be at most `N`, which is checked at compile time.
+    pub fn from_array<const M: usize>(array: [u8; M]) -> Self {
+        const { assert!(M <= N, "array does not fit in Bytes<N>") };
+        let mut bytes = Self::new();
+        bytes.bytes.extend_from_slice(&array).ok();
+        bytes
+    }
+}
+
+/// Copies a byte string literal into a new `Bytes<N>`.
+///
+/// `bytes!(16; b"AT+CSQ\r\n")` returns a `Bytes<16>`. `N` must be a
+/// constant, not a generic parameter. A literal longer than `N` fails to
+/// compile:
+///
+/// ```compile_fail
+/// let bytes = heapless_bytes::bytes!(4; b"AT+CSQ\r\n");
+/// ```
+#[macro_export]
+macro_rules! bytes {
+    ($n:expr; $literal:expr) => {{
+        const LEN: usize = $literal.len();
+        const _: () = assert!(LEN <= $n, "byte string does not fit in Bytes<N>");
+        const ARRAY: [u8; LEN] = *$literal;
+        $crate::Bytes::<$n>::from_array(ARRAY)
+    }};
+}
+
+/// Decodes a hex literal into a new `Bytes<N>`.
+///
+/// `hex_bytes!(4; "deadbeef")` returns a `Bytes<4>`. Upper- and lowercase
+/// digits are accepted, without a `0x` prefix. As with [`bytes!`], `N` must
+/// be a constant. Invalid digits, an odd number of digits, or more than `N`
+/// bytes fail to compile:
+///
+/// ```compile_fail
+/// let bytes = heapless_bytes::hex_bytes!(4; "deadbeefff");
+/// ```
+///
+/// ```compile_fail
+/// let bytes = heapless_bytes::hex_bytes!(4; "deadbeeg");
+/// ```
+#[macro_export]
+macro_rules! hex_bytes {
+    ($n:expr; $hex:expr) => {{
+        const HEX: &str = $hex;
+        const BYTES: [u8; HEX.len() / 2] = $crate::hex::decode_literal(HEX);
+        const _: () = assert!(BYTES.len() <= $n, "hex literal does not fit in Bytes<N>");
+        $crate::Bytes::<$n>::from_array(BYTES)
+    }};
+}
@@ -0,0 +1,49 @@
+//! [`minicbor`](https://docs.rs/minicbor) traits for `Bytes<N>`, encoding
+//! it as a CBOR byte string, the same as its serde implementation does.
+
+use minicbor::{
+    data::Type,
+    decode::{self, Decoder},
+    encode::{self, Encoder, Write},
+    CborLen, Decode, Encode,
+};
+
+use crate::Bytes;
+
+const CAPACITY_EXCEEDED: &str = "byte string exceeds the capacity of Bytes<N>";
+
+impl<C, const N: usize> Encode<C> for Bytes<N> {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _: &mut C,
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(self)?.ok()
+    }
+}
+
+impl<C, const N: usize> CborLen<C> for Bytes<N> {
+    fn cbor_len(&self, ctx: &mut C) -> usize {
+        let n = self.len();
+        n.cbor_len(ctx) + n
+    }
+}
+
+/// Accepts definite and indefinite length byte strings of at most `N` bytes.
+impl<'b, C, const N: usize> Decode<'b, C> for Bytes<N> {
+    fn decode(d: &mut Decoder<'b>, _: &mut C) -> Result<Self, decode::Error> {
+        let pos = d.position();
+        if d.datatype()? != Type::BytesIndef {
+            return Self::from_slice(d.bytes()?)
+                .map_err(|_| decode::Error::message(CAPACITY_EXCEEDED).at(pos));
+        }
+
+        let mut bytes = Self::new();
+        for chunk in d.bytes_iter()? {
+            bytes
+                .extend_from_slice(chunk?)
+                .map_err(|_| decode::Error::message(CAPACITY_EXCEEDED).at(pos))?;
+        }
+        Ok(bytes)
+    }
+}
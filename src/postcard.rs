@@ -0,0 +1,95 @@
+//! [`postcard`](https://docs.rs/postcard) encoding into and decoding out of `Bytes<N>`.
+//!
+//! The plain variants write the postcard wire format as is, the `_cobs`
+//! variants frame it with COBS, terminated by a zero byte, for streams
+//! such as UARTs.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Bytes, CodecError};
+
+/// Length of the COBS framing of `len` bytes, at most: one overhead byte
+/// per started block of 254, and the terminator.
+fn max_cobs_len(len: usize) -> usize {
+    len.saturating_add(len / 254).saturating_add(2)
+}
+
+impl<const N: usize> Bytes<N> {
+    /// Serialize `t` as postcard into a new `Bytes<N>`.
+    pub fn try_from_postcard<T>(t: &T) -> Result<Self, CodecError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut bytes = Self::new();
+        bytes.resize_to_capacity();
+
+        match ::postcard::to_slice(t, &mut bytes) {
+            Ok(written) => {
+                let size = written.len();
+                bytes.truncate(size);
+                Ok(bytes)
+            }
+            Err(::postcard::Error::SerializeBufferFull) => {
+                match ::postcard::experimental::serialized_size(t) {
+                    Ok(requested) => Err(Self::capacity_error(requested).into()),
+                    Err(_) => Err(CodecError::Serialize),
+                }
+            }
+            Err(_) => Err(CodecError::Serialize),
+        }
+    }
+
+    /// Serialize `t` as a COBS-framed postcard message, including the
+    /// terminating zero byte, into a new `Bytes<N>`.
+    ///
+    /// On overflow, `requested` is an upper bound of the framed length.
+    pub fn try_from_postcard_cobs<T>(t: &T) -> Result<Self, CodecError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut bytes = Self::new();
+        bytes.resize_to_capacity();
+
+        match ::postcard::to_slice_cobs(t, &mut bytes) {
+            Ok(written) => {
+                let size = written.len();
+                bytes.truncate(size);
+                Ok(bytes)
+            }
+            Err(::postcard::Error::SerializeBufferFull) => {
+                match ::postcard::experimental::serialized_size(t) {
+                    Ok(len) => Err(Self::capacity_error(max_cobs_len(len)).into()),
+                    Err(_) => Err(CodecError::Serialize),
+                }
+            }
+            Err(_) => Err(CodecError::Serialize),
+        }
+    }
+
+    /// Deserialize the postcard contents into a `T`, which may borrow from `self`.
+    pub fn to_postcard<'de, T>(&'de self) -> Result<T, CodecError>
+    where
+        T: Deserialize<'de>,
+    {
+        match ::postcard::take_from_bytes(self) {
+            Ok((t, [])) => Ok(t),
+            _ => Err(CodecError::Deserialize),
+        }
+    }
+
+    /// Deserialize a COBS-framed postcard message into a `T`, which may
+    /// borrow from `self`.
+    ///
+    /// The frame is decoded in place, so afterwards the contents are no
+    /// longer COBS-encoded. Trailing bytes after the terminating zero byte,
+    /// if there is one, are an error.
+    pub fn to_postcard_cobs<'de, T>(&'de mut self) -> Result<T, CodecError>
+    where
+        T: Deserialize<'de>,
+    {
+        match ::postcard::take_from_bytes_cobs(self) {
+            Ok((t, [])) => Ok(t),
+            _ => Err(CodecError::Deserialize),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! `SecretBytes<N>`, for keys and PINs that must not leak into logs.
+
+use core::fmt;
+
+use ::zeroize::{Zeroize, ZeroizeOnDrop};
+use serde::de::{Deserialize, Deserializer};
+
+use crate::Bytes;
+
+/// `Bytes<N>` holding a secret.
+///
+/// The contents are only reachable through [`expose_secret`](Self::expose_secret)
+/// and [`expose_secret_mut`](Self::expose_secret_mut). `Debug` prints the
+/// length but not the contents, and the buffer is wiped on drop.
+///
+/// `SecretBytes<N>` can be deserialized, but only implements `Serialize`
+/// with the `serialize-secrets` feature.
+#[derive(Clone, Default)]
+pub struct SecretBytes<const N: usize>(Bytes<N>);
+
+impl<const N: usize> SecretBytes<N> {
+    /// Wrap a secret.
+    pub fn new(bytes: Bytes<N>) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the secret.
+    pub fn expose_secret(&self) -> &Bytes<N> {
+        &self.0
+    }
+
+    /// Returns the secret, for changing it in place.
+    pub fn expose_secret_mut(&mut self) -> &mut Bytes<N> {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> From<Bytes<N>> for SecretBytes<N> {
+    fn from(bytes: Bytes<N>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const N: usize> fmt::Debug for SecretBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes<{}>([REDACTED; {}])", N, self.0.len())
+    }
+}
+
+impl<const N: usize> Zeroize for SecretBytes<N> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+// `Bytes<N>` zeroizes on drop with the `zeroize` feature
+impl<const N: usize> ZeroizeOnDrop for SecretBytes<N> {}
+
+#[cfg(feature = "serialize-secrets")]
+impl<const N: usize> serde::ser::Serialize for SecretBytes<N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for SecretBytes<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Bytes::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl<const N: usize> ::subtle::ConstantTimeEq for SecretBytes<N> {
+    fn ct_eq(&self, other: &Self) -> ::subtle::Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
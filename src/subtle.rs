@@ -0,0 +1,121 @@
+//! Constant-time comparison and selection of `Bytes<N>`, using
+//! [`subtle`](https://docs.rs/subtle).
+
+use core::ops::{Deref, DerefMut};
+
+use ::subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+use crate::Bytes;
+
+impl<const N: usize> Bytes<N> {
+    /// Compares the contents with `other` in constant time.
+    ///
+    /// All `N` bytes of capacity are visited, or all of `other` if it is
+    /// longer, so the running time depends neither on where the contents
+    /// differ nor on whether the lengths do.
+    pub fn ct_eq(&self, other: &[u8]) -> Choice {
+        let mut eq = (self.len() as u64).ct_eq(&(other.len() as u64));
+        for i in 0..N.max(other.len()) {
+            let a = self.get(i).copied().unwrap_or_default();
+            let b = other.get(i).copied().unwrap_or_default();
+            eq &= a.ct_eq(&b);
+        }
+        eq
+    }
+
+    /// Returns a copy of `a` if `choice` is 0, and of `b` if it is 1, in
+    /// constant time.
+    ///
+    /// `subtle`'s `ConditionallySelectable` requires `Copy`, which `Bytes<N>`
+    /// is not, so this and [`conditional_assign`](Self::conditional_assign)
+    /// and [`conditional_swap`](Self::conditional_swap) stand in for it.
+    pub fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut bytes = a.clone();
+        bytes.conditional_assign(b, choice);
+        bytes
+    }
+
+    /// Overwrites `self` with `other` if `choice` is 1, in constant time.
+    pub fn conditional_assign(&mut self, other: &Self, choice: Choice) {
+        let len = u64::conditional_select(&(self.len() as u64), &(other.len() as u64), choice);
+        self.resize_to_capacity();
+        for (i, byte) in self.iter_mut().enumerate() {
+            byte.conditional_assign(&other.get(i).copied().unwrap_or_default(), choice);
+        }
+        self.truncate(len as usize);
+    }
+
+    /// Swaps `a` and `b` if `choice` is 1, in constant time.
+    pub fn conditional_swap(a: &mut Self, b: &mut Self, choice: Choice) {
+        let a_len = a.len() as u64;
+        let b_len = b.len() as u64;
+        a.resize_to_capacity();
+        b.resize_to_capacity();
+        for (a, b) in a.iter_mut().zip(b.iter_mut()) {
+            u8::conditional_swap(a, b, choice);
+        }
+        a.truncate(u64::conditional_select(&a_len, &b_len, choice) as usize);
+        b.truncate(u64::conditional_select(&b_len, &a_len, choice) as usize);
+    }
+}
+
+impl<const N: usize> ConstantTimeEq for Bytes<N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        Bytes::ct_eq(self, other)
+    }
+}
+
+/// `Bytes<N>` whose `==` compares in constant time, using [`Bytes::ct_eq`].
+///
+/// Comparing the `Bytes<N>` it dereferences to still short-circuits.
+#[derive(Clone, Debug, Default)]
+pub struct CtBytes<const N: usize>(pub Bytes<N>);
+
+impl<const N: usize> From<Bytes<N>> for CtBytes<N> {
+    fn from(bytes: Bytes<N>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const N: usize> From<CtBytes<N>> for Bytes<N> {
+    fn from(bytes: CtBytes<N>) -> Self {
+        bytes.0
+    }
+}
+
+impl<const N: usize> Deref for CtBytes<N> {
+    type Target = Bytes<N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for CtBytes<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for CtBytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<Rhs, const N: usize> PartialEq<Rhs> for CtBytes<N>
+where
+    Rhs: ?Sized + AsRef<[u8]>,
+{
+    fn eq(&self, other: &Rhs) -> bool {
+        self.0.ct_eq(other.as_ref()).into()
+    }
+}
+
+impl<const N: usize> Eq for CtBytes<N> {}
+
+impl<const N: usize> ConstantTimeEq for CtBytes<N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
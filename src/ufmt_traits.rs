@@ -0,0 +1,43 @@
+//! [`ufmt`](https://docs.rs/ufmt) traits for `Bytes<N>`, to format into it
+//! and to format it without the code size of `core::fmt`.
+
+use ufmt::{uDebug, uDisplay, uWrite, Formatter};
+
+use crate::{
+    hex::{write_hex, Hex, DIGITS},
+    Bytes, CapacityError,
+};
+
+/// Appends UTF-8 text, failing when the buffer is full.
+///
+/// Unlike with `core::fmt::Write`, a failed `uwrite!` keeps the part of the
+/// output that did fit.
+impl<const N: usize> uWrite for Bytes<N> {
+    type Error = CapacityError;
+
+    fn write_str(&mut self, s: &str) -> Result<(), CapacityError> {
+        let requested = self.len().saturating_add(s.len());
+        self.bytes
+            .extend_from_slice(s.as_bytes())
+            .map_err(|_| Self::capacity_error(requested))
+    }
+}
+
+/// Writes the contents as lowercase hex digits.
+impl<const N: usize> uDebug for Bytes<N> {
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        write_hex(self, DIGITS, |s| f.write_str(s))
+    }
+}
+
+impl uDisplay for Hex<'_> {
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        write_hex(self.0, DIGITS, |s| f.write_str(s))
+    }
+}
@@ -0,0 +1,199 @@
+//! `BytesView`, a `Bytes<N>` with the capacity erased from its type.
+
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{Bytes, CapacityError, IndexError, InsertError};
+
+/// What `BytesView` needs from a `Bytes<N>`, dispatched at runtime so that
+/// its methods are compiled once rather than once per `N`.
+trait Storage: fmt::Debug {
+    fn as_slice(&self) -> &[u8];
+    fn as_mut_slice(&mut self) -> &mut [u8];
+    fn capacity(&self) -> usize;
+    fn resize_default(&mut self, new_len: usize) -> Result<(), CapacityError>;
+}
+
+impl<const N: usize> Storage for Bytes<N> {
+    fn as_slice(&self) -> &[u8] {
+        Bytes::as_slice(self)
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        Bytes::as_mut_slice(self)
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    fn resize_default(&mut self, new_len: usize) -> Result<(), CapacityError> {
+        Bytes::resize_default(self, new_len)
+    }
+}
+
+/// Unsized view of a `Bytes<N>` of any capacity, returned by
+/// [`Bytes::as_view`] and [`Bytes::as_mut_view`].
+///
+/// Functions taking `&mut BytesView` serve every capacity without being
+/// generic over `N`. Shrinking methods wipe like those of `Bytes<N>` with
+/// the `zeroize-on-shrink` feature.
+#[repr(transparent)]
+pub struct BytesView(dyn Storage);
+
+impl<const N: usize> Bytes<N> {
+    /// Returns a view with the capacity erased.
+    #[inline]
+    pub fn as_view(&self) -> &BytesView {
+        let storage: &dyn Storage = self;
+        // SAFETY: `BytesView` is a transparent wrapper of `dyn Storage`
+        unsafe { &*(storage as *const dyn Storage as *const BytesView) }
+    }
+
+    /// Returns a mutable view with the capacity erased.
+    #[inline]
+    pub fn as_mut_view(&mut self) -> &mut BytesView {
+        let storage: &mut dyn Storage = self;
+        // SAFETY: `BytesView` is a transparent wrapper of `dyn Storage`
+        unsafe { &mut *(storage as *mut dyn Storage as *mut BytesView) }
+    }
+}
+
+impl BytesView {
+    /// Returns the capacity of the underlying `Bytes<N>`, that is `N`.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    /// Appends a byte.
+    #[inline]
+    pub fn push(&mut self, byte: u8) -> Result<(), CapacityError> {
+        self.extend_from_slice(&[byte])
+    }
+
+    /// Appends the bytes of `slice`, or none of them if they do not all fit.
+    #[inline]
+    pub fn extend_from_slice(&mut self, slice: &[u8]) -> Result<(), CapacityError> {
+        let len = self.0.as_slice().len();
+        self.0.resize_default(len.saturating_add(slice.len()))?;
+        if let Some(tail) = self.0.as_mut_slice().get_mut(len..) {
+            for (byte, &value) in tail.iter_mut().zip(slice) {
+                *byte = value;
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts the bytes of `slice` at index `at`, shifting the rest back.
+    #[inline]
+    pub fn insert_slice_at<'s>(
+        &mut self,
+        slice: &'s [u8],
+        at: usize,
+    ) -> Result<(), InsertError<&'s [u8]>> {
+        let l = slice.len();
+        let before = self.0.as_slice().len();
+
+        if at > before {
+            return Err(InsertError {
+                error: IndexError {
+                    index: at,
+                    len: before,
+                }
+                .into(),
+                value: slice,
+            });
+        }
+
+        if let Err(error) = self.0.resize_default(before.saturating_add(l)) {
+            return Err(InsertError {
+                error: error.into(),
+                value: slice,
+            });
+        }
+
+        // move back existing, the `l` bytes past them are new
+        if let Some(tail) = self.0.as_mut_slice().get_mut(at..) {
+            if let Some(existing) = tail.len().checked_sub(l) {
+                tail.copy_within(..existing, l);
+            }
+            for (byte, &value) in tail.iter_mut().zip(slice) {
+                *byte = value;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes the byte at `index` and returns it, shifting the rest forward.
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> Result<u8, IndexError> {
+        let raw = self.0.as_mut_slice();
+        let len = raw.len();
+        let Some(&byte) = raw.get(index) else {
+            return Err(IndexError { index, len });
+        };
+
+        raw.copy_within(index + 1.., index);
+        self.0.resize_default(len - 1).ok();
+        Ok(byte)
+    }
+
+    /// Shortens the buffer to `len` bytes, if it is longer.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.0.as_slice().len() {
+            self.0.resize_default(len).ok();
+        }
+    }
+
+    /// Removes all bytes.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl fmt::Debug for BytesView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl AsRef<[u8]> for BytesView {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+impl AsMut<[u8]> for BytesView {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.0.as_mut_slice()
+    }
+}
+
+impl Deref for BytesView {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_slice()
+    }
+}
+
+impl DerefMut for BytesView {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0.as_mut_slice()
+    }
+}
+
+impl<Rhs> PartialEq<Rhs> for BytesView
+where
+    Rhs: ?Sized + AsRef<[u8]>,
+{
+    fn eq(&self, other: &Rhs) -> bool {
+        self.as_ref().eq(other.as_ref())
+    }
+}
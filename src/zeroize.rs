@@ -0,0 +1,23 @@
+//! [`zeroize`](https://docs.rs/zeroize) support for `Bytes<N>`.
+
+use ::zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::Bytes;
+
+impl<const N: usize> Zeroize for Bytes<N> {
+    /// Wipes the whole buffer, including the capacity past the length, and
+    /// clears it.
+    fn zeroize(&mut self) {
+        self.resize_to_capacity();
+        self.bytes.as_mut_slice().zeroize();
+        self.bytes.clear();
+    }
+}
+
+impl<const N: usize> Drop for Bytes<N> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const N: usize> ZeroizeOnDrop for Bytes<N> {}
@@ -0,0 +1,182 @@
+//! Link-time check that the public `Bytes<N>` API cannot panic.
+//!
+//! Each wrapper is annotated with `#[no_panic]`, which fails to link if the
+//! optimizer cannot prove the body panic-free. The check needs optimizations,
+//! so it is only active in release builds: `cargo test --release`.
+
+use heapless::String;
+use heapless_bytes::{Bytes, CapacityError, HexError, IndexError, InsertError, TryFromError};
+
+#[cfg(not(debug_assertions))]
+use no_panic::no_panic;
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn from_slice(slice: &[u8]) -> Result<Bytes<8>, CapacityError> {
+    Bytes::from_slice(slice)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn from_array(array: [u8; 4]) -> Bytes<8> {
+    Bytes::from_array(array)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn try_convert_into(bytes: &Bytes<8>) -> Result<Bytes<4>, CapacityError> {
+    bytes.try_convert_into()
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn to_bytes(bytes: &Bytes<8>) -> Result<Bytes<4>, CapacityError> {
+    bytes.to_bytes()
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn widen(bytes: &Bytes<8>) -> Bytes<16> {
+    bytes.widen()
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn concat(bytes: &Bytes<8>, other: &Bytes<8>) -> Bytes<16> {
+    bytes.concat(other)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn try_from(count: usize) -> Result<Bytes<8>, TryFromError<()>> {
+    Bytes::try_from(|_| Ok(count))
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn insert_slice_at<'s>(
+    bytes: &mut Bytes<8>,
+    slice: &'s [u8],
+    at: usize,
+) -> Result<(), InsertError<&'s [u8]>> {
+    bytes.insert_slice_at(slice, at)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn insert(bytes: &mut Bytes<8>, index: usize, item: u8) -> Result<(), InsertError<u8>> {
+    bytes.insert(index, item)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn remove(bytes: &mut Bytes<8>, index: usize) -> Result<u8, IndexError> {
+    bytes.remove(index)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn resize_default(bytes: &mut Bytes<8>, new_len: usize) -> Result<(), CapacityError> {
+    bytes.resize_default(new_len)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn resize_to_capacity(bytes: &mut Bytes<8>) {
+    bytes.resize_to_capacity()
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn truncate(bytes: &mut Bytes<8>, len: usize) {
+    bytes.truncate(len)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn pop(bytes: &mut Bytes<8>) -> Option<u8> {
+    bytes.pop()
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn resize(bytes: &mut Bytes<8>, new_len: usize) -> Result<(), CapacityError> {
+    bytes.resize(new_len, b'x')
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn swap_remove(bytes: &mut Bytes<8>, index: usize) -> Result<u8, IndexError> {
+    bytes.swap_remove(index)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn retain(bytes: &mut Bytes<8>, byte: u8) {
+    bytes.retain(|&b| b != byte)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn view_extend_from_slice(bytes: &mut Bytes<8>, slice: &[u8]) -> Result<(), CapacityError> {
+    bytes.as_mut_view().extend_from_slice(slice)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn view_insert_slice_at<'s>(
+    bytes: &mut Bytes<8>,
+    slice: &'s [u8],
+    at: usize,
+) -> Result<(), InsertError<&'s [u8]>> {
+    bytes.as_mut_view().insert_slice_at(slice, at)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn view_remove(bytes: &mut Bytes<8>, index: usize) -> Result<u8, IndexError> {
+    bytes.as_mut_view().remove(index)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn from_hex(hex: &str) -> Result<Bytes<8>, HexError> {
+    Bytes::from_hex(hex)
+}
+
+#[cfg_attr(not(debug_assertions), no_panic)]
+fn to_hex(bytes: &Bytes<8>) -> String<16> {
+    bytes.to_hex()
+}
+
+#[test]
+fn out_of_range_calls_return_errors() {
+    assert!(from_slice(&[0; 9]).is_err());
+    assert_eq!(from_array([1, 2, 3, 4]), [1, 2, 3, 4]);
+    let mut bytes = from_slice(b"12345").unwrap();
+    assert!(try_convert_into(&bytes).is_err());
+    assert!(to_bytes(&bytes).is_err());
+    assert_eq!(widen(&bytes), b"12345");
+    assert_eq!(concat(&bytes, &bytes), b"1234512345");
+    assert!(try_from(9).is_err());
+
+    assert!(insert_slice_at(&mut bytes, b"6", 6).is_err());
+    assert!(insert_slice_at(&mut bytes, b"6789", 5).is_err());
+    assert!(insert(&mut bytes, 6, b'6').is_err());
+    assert!(remove(&mut bytes, 5).is_err());
+    assert!(resize_default(&mut bytes, 9).is_err());
+    assert_eq!(bytes, b"12345");
+
+    insert_slice_at(&mut bytes, b"ab", 1).unwrap();
+    insert(&mut bytes, 7, b'c').unwrap();
+    assert_eq!(bytes, b"1ab2345c");
+    assert_eq!(remove(&mut bytes, 0), Ok(b'1'));
+    resize_to_capacity(&mut bytes);
+    assert_eq!(bytes, b"ab2345c\0");
+
+    truncate(&mut bytes, 9);
+    truncate(&mut bytes, 1);
+    assert_eq!(pop(&mut bytes), Some(b'a'));
+    assert_eq!(pop(&mut bytes), None);
+    assert!(resize(&mut bytes, 9).is_err());
+    resize(&mut bytes, 4).unwrap();
+    assert!(swap_remove(&mut bytes, 4).is_err());
+    assert_eq!(swap_remove(&mut bytes, 0), Ok(b'x'));
+    retain(&mut bytes, b'x');
+    assert!(bytes.is_empty());
+
+    assert!(view_extend_from_slice(&mut bytes, &[0; 9]).is_err());
+    view_extend_from_slice(&mut bytes, b"1234").unwrap();
+    assert!(view_insert_slice_at(&mut bytes, b"5", 5).is_err());
+    assert!(view_insert_slice_at(&mut bytes, b"56789", 4).is_err());
+    view_insert_slice_at(&mut bytes, b"ab", 1).unwrap();
+    assert!(view_remove(&mut bytes, 6).is_err());
+    assert_eq!(view_remove(&mut bytes, 0), Ok(b'1'));
+    assert_eq!(bytes, b"ab234");
+    bytes.clear();
+
+    assert!(from_hex("123").is_err());
+    assert!(from_hex("0x").is_err());
+    assert!(from_hex(&"00".repeat(9)).is_err());
+    let bytes = from_hex("c0ffee").unwrap();
+    assert_eq!(to_hex(&bytes), "c0ffee");
+}